@@ -5,14 +5,64 @@
 //!
 
 use crate::media::MediaStreamTrackKind;
+use crate::settings::SettingsRegistry;
+use crate::state::AppState;
+use crate::tray::{TrayHandle, WeakTrayHandle};
+use crate::window::{
+    CloseContext, CloseDecision, CloseHandler, WindowBuilder, WindowEvent, WindowHandle,
+};
+use crate::window_state::WindowStateKeeper;
+use futures::channel::oneshot;
 use nw_sys::{prelude::*, result::Result, utils};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{MediaStream, MediaStreamTrack, MouseEvent};
+use web_sys::{MediaStream, MediaStreamTrack, MouseEvent, Notification};
+use workflow_log::log_error;
 use workflow_wasm::prelude::*;
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["nw", "App"], js_name = on)]
+    fn app_on(event: &str, callback: &js_sys::Function);
+}
+
+/// Async hook run by [`Application::quit()`]
+pub type ShutdownHook = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<()>>>>>;
+
+/// Id of a hook registered with [`Application::on_shutdown()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShutdownHookId(u64);
+
+static NEXT_SHUTDOWN_HOOK_ID: AtomicU64 = AtomicU64::new(0);
+
 static mut APP: Option<Arc<Application>> = None;
 
+/// Reservation of a window id in [`Application::opening_windows`].
+/// Released when dropped, also if opening fails or is cancelled,
+/// passing the opened window (if any) to the waiting calls.
+struct OpeningWindow<'a> {
+    app: &'a Application,
+    id: &'a str,
+    window: Option<WindowHandle>,
+}
+
+impl Drop for OpeningWindow<'_> {
+    fn drop(&mut self) {
+        let waiting = match self.app.opening_windows.lock() {
+            Ok(mut opening) => opening.remove(self.id).unwrap_or_default(),
+            Err(_) => return,
+        };
+        for sender in waiting {
+            let _ = sender.send(self.window.clone());
+        }
+    }
+}
+
 /// get saved [Application](Application) instance.
 pub fn app() -> Option<Arc<Application>> {
     unsafe { APP.clone() }
@@ -30,6 +80,42 @@ pub struct Application {
 
     /// holds references to [Callback](workflow_wasm::callback::Callback)
     pub callbacks: CallbackMap,
+
+    /// open windows keyed by their id
+    pub windows: Arc<Mutex<HashMap<String, WindowHandle>>>,
+
+    /// ids of windows being opened by [`Application::focus_or_open()`],
+    /// with the calls waiting for them
+    pub opening_windows: Arc<Mutex<HashMap<String, Vec<oneshot::Sender<Option<WindowHandle>>>>>>,
+
+    /// cleanup callbacks of [`WindowHandle::on()`] subscriptions,
+    /// keyed by the id of the subscribed callback
+    pub listener_cleanups: Arc<Mutex<HashMap<CallbackId, CallbackId>>>,
+
+    /// optional window geometry persistence
+    pub window_state: Arc<Mutex<Option<WindowStateKeeper>>>,
+
+    /// close request handlers registered via [`WindowHandle::on_close_requested()`]
+    pub close_handlers: Arc<Mutex<Vec<(WindowHandle, CloseHandler)>>>,
+
+    /// hooks run by [`Application::quit()`] in registration order
+    pub shutdown_hooks: Arc<Mutex<Vec<(ShutdownHookId, ShutdownHook)>>>,
+
+    /// global shortcuts registered via [`Application::register_global_shortcut()`]
+    pub shortcuts: Arc<Mutex<Vec<nw_sys::Shortcut>>>,
+
+    /// trays created by [`TrayMenuBuilder::build()`](crate::tray::TrayMenuBuilder::build)
+    pub trays: Arc<Mutex<Vec<WeakTrayHandle>>>,
+
+    /// trays kept alive by [`TrayHandle::detach()`](crate::tray::TrayHandle::detach)
+    pub detached_trays: Arc<Mutex<Vec<TrayHandle>>>,
+
+    /// settings registered via [`Application::register_settings()`]
+    pub settings: SettingsRegistry,
+
+    /// notifications with a tag shown by [`NotificationBuilder::build()`](crate::notification::NotificationBuilder::build),
+    /// with the ids of their callbacks, keyed by tag
+    pub tagged_notifications: Arc<Mutex<HashMap<String, (Notification, Vec<CallbackId>)>>>,
 }
 
 impl Application {
@@ -42,6 +128,17 @@ impl Application {
         let app = Arc::new(Self {
             callbacks: CallbackMap::new(),
             media_stream: Arc::new(Mutex::new(None)),
+            windows: Arc::new(Mutex::new(HashMap::new())),
+            opening_windows: Arc::new(Mutex::new(HashMap::new())),
+            listener_cleanups: Arc::new(Mutex::new(HashMap::new())),
+            window_state: Arc::new(Mutex::new(None)),
+            close_handlers: Arc::new(Mutex::new(Vec::new())),
+            shutdown_hooks: Arc::new(Mutex::new(Vec::new())),
+            shortcuts: Arc::new(Mutex::new(Vec::new())),
+            trays: Arc::new(Mutex::new(Vec::new())),
+            detached_trays: Arc::new(Mutex::new(Vec::new())),
+            settings: Arc::new(Mutex::new(HashMap::new())),
+            tagged_notifications: Arc::new(Mutex::new(HashMap::new())),
         });
 
         unsafe {
@@ -51,6 +148,15 @@ impl Application {
         Ok(app)
     }
 
+    /// Attach to the [`AppState`] `name` shared by all application windows,
+    /// initializing it with `T::default()` if it does not exist yet.
+    pub fn state<T>(&self, name: &str) -> Result<AppState<T>>
+    where
+        T: Serialize + DeserializeOwned + Default + 'static,
+    {
+        AppState::new(name)
+    }
+
     /// Store or Clear saved [MediaStream](web_sys::MediaStream)
     pub fn set_media_stream(&self, media_stream: Option<MediaStream>) -> Result<()> {
         *self.media_stream.lock()? = media_stream;
@@ -115,14 +221,358 @@ impl Application {
     where
         F: FnMut(nw_sys::Window) -> std::result::Result<(), JsValue> + 'static,
     {
-        let callback = Callback::new(callback);
+        let id = js_sys::Reflect::get(option, &JsValue::from("id"))?.as_string();
+        let option = match id.as_ref() {
+            Some(id) => self.apply_window_state(id, option)?,
+            None => option.clone(),
+        };
+        let mut callback = callback;
+        let callback = Callback::new(
+            move |win: nw_sys::Window| -> std::result::Result<(), JsValue> {
+                if let (Some(id), Some(app)) = (id.as_ref(), app()) {
+                    app.register_window(id, WindowHandle::from(win.clone()))?;
+                }
+                callback(win)
+            },
+        );
 
-        nw_sys::window::open_with_options_and_callback(url, option, callback.as_ref());
+        nw_sys::window::open_with_options_and_callback(url, &option, callback.as_ref());
 
         self.callbacks.retain(callback)?;
         Ok(())
     }
 
+    /// Enable or disable window geometry persistence. When enabled, windows
+    /// created with an `id` via [`create_window_with_callback()`](Self::create_window_with_callback)
+    /// or [`WindowBuilder`] reopen with their last position, size and
+    /// maximized/fullscreen state.
+    pub fn set_window_state_keeper(&self, keeper: Option<WindowStateKeeper>) -> Result<()> {
+        *self.window_state.lock()? = keeper;
+        Ok(())
+    }
+
+    /// Get the [`WindowStateKeeper`] if window geometry persistence is enabled
+    pub fn window_state_keeper(&self) -> Result<Option<WindowStateKeeper>> {
+        Ok(self.window_state.lock()?.clone())
+    }
+
+    pub(crate) fn apply_window_state(
+        &self,
+        id: &str,
+        option: &nw_sys::window::Options,
+    ) -> Result<nw_sys::window::Options> {
+        match self.window_state_keeper()? {
+            Some(keeper) => keeper.apply(id, option),
+            None => Ok(option.clone()),
+        }
+    }
+
+    /// Track `window` under the given `id`. The window is removed from the
+    /// registry when it emits the `closed` event.
+    ///
+    /// Windows created via [`WindowBuilder`] or [`create_window_with_callback()`](Self::create_window_with_callback)
+    /// with an `id` option are registered automatically.
+    pub fn register_window(&self, id: &str, window: WindowHandle) -> Result<()> {
+        let id_ = id.to_string();
+        let window_ = window.clone();
+        window.on(WindowEvent::Closed, move |_| {
+            if let Some(app) = app() {
+                let mut windows = app.windows.lock().map_err(|err| format!("{:?}", err))?;
+                if windows.get(&id_) == Some(&window_) {
+                    windows.remove(&id_);
+                }
+            }
+            Ok(())
+        })?;
+        if let Some(keeper) = self.window_state_keeper()? {
+            keeper.track(id, &window)?;
+        }
+        self.windows.lock()?.insert(id.to_string(), window);
+
+        Ok(())
+    }
+
+    /// Get the open window registered under `id`
+    pub fn window(&self, id: &str) -> Result<Option<WindowHandle>> {
+        Ok(self.windows.lock()?.get(id).cloned())
+    }
+
+    /// Get all open windows together with their ids
+    pub fn windows(&self) -> Result<Vec<(String, WindowHandle)>> {
+        let windows = self
+            .windows
+            .lock()?
+            .iter()
+            .map(|(id, win)| (id.clone(), win.clone()))
+            .collect();
+        Ok(windows)
+    }
+
+    /// Focus the window registered under `id` or, if it is not open,
+    /// create it using the supplied [`WindowBuilder`].
+    ///
+    /// The id is reserved while the window opens: concurrent calls
+    /// with the same id wait for it instead of opening another window.
+    pub async fn focus_or_open(&self, id: &str, builder: WindowBuilder) -> Result<WindowHandle> {
+        if let Some(window) = self.window(id)? {
+            window.restore();
+            window.show();
+            window.focus();
+            return Ok(window);
+        }
+
+        let receiver = {
+            let mut opening = self.opening_windows.lock()?;
+            match opening.get_mut(id) {
+                Some(waiting) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiting.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    opening.insert(id.to_string(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            return match receiver.await {
+                Ok(Some(window)) => {
+                    window.focus();
+                    Ok(window)
+                }
+                _ => Err(format!("window `{}` could not be opened", id).into()),
+            };
+        }
+
+        let mut guard = OpeningWindow {
+            app: self,
+            id,
+            window: None,
+        };
+        let window = builder.id(id).build().await?;
+        guard.window = Some(window.clone());
+        Ok(window)
+    }
+
+    /// Close the window registered under `id`.
+    /// Returns `false` if no such window is open.
+    pub fn close(&self, id: &str) -> Result<bool> {
+        match self.window(id)? {
+            Some(window) => {
+                window.close();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Close all windows registered with this application.
+    pub fn close_all(&self) -> Result<()> {
+        for (_, window) in self.windows()? {
+            window.close();
+        }
+        Ok(())
+    }
+
+    /// Store the close handler of `window`. Returns `true` if the window
+    /// already had a handler, which has now been replaced.
+    pub(crate) fn set_close_handler(
+        &self,
+        window: &WindowHandle,
+        handler: CloseHandler,
+    ) -> Result<bool> {
+        let mut handlers = self.close_handlers.lock()?;
+        match handlers.iter_mut().find(|(win, _)| win == window) {
+            Some((_, existing)) => {
+                *existing = handler;
+                Ok(true)
+            }
+            None => {
+                handlers.push((window.clone(), handler));
+                Ok(false)
+            }
+        }
+    }
+
+    pub(crate) fn close_handler(&self, window: &WindowHandle) -> Result<Option<CloseHandler>> {
+        let handlers = self.close_handlers.lock()?;
+        Ok(handlers
+            .iter()
+            .find(|(win, _)| win == window)
+            .map(|(_, handler)| handler.clone()))
+    }
+
+    pub(crate) fn remove_close_handler(&self, window: &WindowHandle) -> Result<()> {
+        self.close_handlers.lock()?.retain(|(win, _)| win != window);
+        Ok(())
+    }
+
+    /// Ask every window with a close handler (see [`WindowHandle::on_close_requested()`])
+    /// whether it may close. Handlers run one after another, so confirmation
+    /// dialogs do not overlap. If no handler vetoes, all these windows and all
+    /// registered windows are closed and `true` is returned; otherwise no window
+    /// is closed and `false` is returned.
+    pub async fn request_close_all(&self) -> Result<bool> {
+        if !self.confirm_close_all().await? {
+            return Ok(false);
+        }
+
+        let handlers = self.close_handlers.lock()?.clone();
+        for (window, _) in handlers {
+            window.close_with_force();
+        }
+        for (_, window) in self.windows()? {
+            window.close_with_force();
+        }
+
+        Ok(true)
+    }
+
+    async fn confirm_close_all(&self) -> Result<bool> {
+        let handlers = self.close_handlers.lock()?.clone();
+        for (window, handler) in handlers.iter() {
+            let ctx = CloseContext {
+                window: window.clone(),
+                quit: true,
+            };
+            if handler(ctx).await == CloseDecision::Veto {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Called when the user launches the application again while it is
+    /// running. The callback receives the command line of the new instance.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#event-openargs)
+    pub fn on_open<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(String) -> Result<()> + 'static,
+    {
+        let callback = Callback::new(move |args: JsValue| -> std::result::Result<(), JsValue> {
+            callback(args.as_string().unwrap_or_default())?;
+            Ok(())
+        });
+        app_on("open", callback.as_ref());
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    /// (Mac) Called when the user clicks the dock icon of the running application.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#event-reopen-mac)
+    pub fn on_reopen<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut() -> Result<()> + 'static,
+    {
+        let callback = Callback::new(move |_: JsValue| -> std::result::Result<(), JsValue> {
+            callback()?;
+            Ok(())
+        });
+        app_on("reopen", callback.as_ref());
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    /// Register a hook run by [`quit()`](Self::quit), e.g. to flush
+    /// unsaved data. Hooks run one after another in registration order.
+    ///
+    /// Returns an id for [`remove_shutdown_hook()`](Self::remove_shutdown_hook).
+    pub fn on_shutdown<F, Fut>(&self, hook: F) -> Result<ShutdownHookId>
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<()>> + 'static,
+    {
+        let id = ShutdownHookId(NEXT_SHUTDOWN_HOOK_ID.fetch_add(1, Ordering::Relaxed));
+        let hook: ShutdownHook = Arc::new(move || Box::pin(hook()));
+        self.shutdown_hooks.lock()?.push((id, hook));
+        Ok(id)
+    }
+
+    /// Unregister a hook registered with [`on_shutdown()`](Self::on_shutdown).
+    /// Returns `false` if the hook was already removed.
+    pub fn remove_shutdown_hook(&self, id: ShutdownHookId) -> Result<bool> {
+        let mut hooks = self.shutdown_hooks.lock()?;
+        let len = hooks.len();
+        hooks.retain(|(hook_id, _)| *hook_id != id);
+        Ok(hooks.len() != len)
+    }
+
+    /// Register a global keyboard shortcut. Shortcuts registered this way
+    /// are unregistered by [`quit()`](Self::quit).
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appregisterglobalhotkeyshortcut)
+    pub fn register_global_shortcut(&self, shortcut: &nw_sys::Shortcut) -> Result<()> {
+        nw_sys::app::register_global_hot_key(shortcut);
+        self.shortcuts.lock()?.push(shortcut.clone());
+        Ok(())
+    }
+
+    /// Unregister all shortcuts registered via
+    /// [`register_global_shortcut()`](Self::register_global_shortcut)
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appunregisterglobalhotkeyshortcut)
+    pub fn unregister_global_shortcuts(&self) -> Result<()> {
+        for shortcut in self.shortcuts.lock()?.drain(..) {
+            nw_sys::app::unregister_global_hot_key(&shortcut);
+        }
+        Ok(())
+    }
+
+    /// Remove all trays created by [`TrayMenuBuilder::build()`](crate::tray::TrayMenuBuilder::build)
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#trayremove)
+    pub fn remove_trays(&self) -> Result<()> {
+        let trays = self.trays.lock()?.drain(..).collect::<Vec<_>>();
+        for tray in trays.iter().filter_map(WeakTrayHandle::upgrade) {
+            tray.remove()?;
+        }
+        self.detached_trays.lock()?.clear();
+        Ok(())
+    }
+
+    /// Quit the application gracefully:
+    ///
+    /// 1. ask the close handlers of all windows, see [`request_close_all()`](Self::request_close_all),
+    /// 2. run the shutdown hooks registered with [`on_shutdown()`](Self::on_shutdown),
+    /// 3. stop the saved [MediaStream](web_sys::MediaStream) and save window states,
+    /// 4. unregister global shortcuts and remove trays,
+    /// 5. quit via `nw.App.quit()`.
+    ///
+    /// Returns `false` if a close handler vetoed, in which case nothing else is done.
+    /// Errors of shutdown hooks are logged and do not stop the shutdown.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appquit)
+    pub async fn quit(&self) -> Result<bool> {
+        if !self.confirm_close_all().await? {
+            return Ok(false);
+        }
+
+        let hooks = self.shutdown_hooks.lock()?.clone();
+        for (_, hook) in hooks {
+            if let Err(err) = hook().await {
+                log_error!("shutdown hook error: {:?}", err);
+            }
+        }
+
+        if let Err(err) = self.stop_media_stream(None, None) {
+            log_error!("unable to stop media stream: {:?}", err);
+        }
+        self.set_media_stream(None)?;
+        // windows may not emit `closed` when the app quits
+        if let Some(keeper) = self.window_state_keeper()? {
+            keeper.save()?;
+        }
+        self.unregister_global_shortcuts()?;
+        self.remove_trays()?;
+
+        nw_sys::app::quit();
+        Ok(true)
+    }
+
     /// Create window with given [Options](nw_sys::window::Options)
     pub fn create_window(url: &str, option: &nw_sys::window::Options) -> Result<()> {
         nw_sys::window::open_with_options(url, option);
@@ -0,0 +1,609 @@
+//!
+//! Command-line argument parsing.
+//!
+//! [`ArgsParser`] declares the flags, options and positional arguments of
+//! the application and parses `nw.App.argv` (see [`Application::args()`])
+//! or the arguments of a second launch (see [`LaunchRequest::argv`](crate::launch::LaunchRequest::argv)).
+//! Types implementing [`FromArgs`] map the parsed arguments to a struct.
+//! Parsing does not depend on NW.
+//!
+//! # Synopsis
+//! ```rust
+//! struct Options {
+//!     verbose: bool,
+//!     profile: String,
+//!     files: Vec<String>,
+//! }
+//!
+//! impl FromArgs for Options {
+//!     fn parser() -> ArgsParser {
+//!         ArgsParser::new("my-app")
+//!             .about("Edits documents")
+//!             .flag("verbose", Some('v'), "Print diagnostic output")
+//!             .option("profile", Some('p'), "NAME", "Profile to use")
+//!             .default_value("profile", "default")
+//!             .positional("files", "Documents to open", Arity::Many)
+//!     }
+//!
+//!     fn from_args(args: &ParsedArgs) -> Result<Self> {
+//!         Ok(Self {
+//!             verbose: args.flag("verbose"),
+//!             profile: args.value("profile").unwrap_or_default().to_string(),
+//!             files: args.values("files").to_vec(),
+//!         })
+//!     }
+//! }
+//!
+//! let app = Application::new()?;
+//! match Options::from_argv(&app.args()) {
+//!     Ok(options) => run(options)?,
+//!     // also returned for `--help`
+//!     Err(err) => log_info!("{}", err),
+//! }
+//!
+//! // arguments of a second launch
+//! app.on_launch_request(Some("main"), |request| {
+//!     let options = Options::from_argv(&request.argv)?;
+//!     open_documents(&options.files)
+//! })?;
+//! ```
+//!
+
+use crate::application::Application;
+use nw_sys::result::Result;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(thread_local_v2, js_namespace = ["nw", "App"], js_name = argv)]
+    static ARGV: js_sys::Array;
+
+    #[wasm_bindgen(thread_local_v2, js_namespace = ["nw", "App"], js_name = fullArgv)]
+    static FULL_ARGV: js_sys::Array;
+}
+
+fn to_strings(array: &js_sys::Array) -> Vec<String> {
+    array.iter().filter_map(|arg| arg.as_string()).collect()
+}
+
+impl Application {
+    /// Arguments passed to the application, without the NW executable,
+    /// the application path and switches recognized by NW/Chromium.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appargv)
+    pub fn args(&self) -> Vec<String> {
+        ARGV.with(to_strings)
+    }
+
+    /// All arguments passed to the application, including switches
+    /// recognized by NW/Chromium.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appfullargv)
+    pub fn full_args(&self) -> Vec<String> {
+        FULL_ARGV.with(to_strings)
+    }
+}
+
+/// Number of values a positional argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// exactly one value
+    Required,
+    /// zero or one value
+    Optional,
+    /// any number of values; only valid for the last positional argument
+    Many,
+}
+
+#[derive(Debug, Clone)]
+struct Switch {
+    name: String,
+    short: Option<char>,
+    value_name: Option<String>,
+    help: String,
+    default: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Positional {
+    name: String,
+    help: String,
+    arity: Arity,
+}
+
+/// Parsed command-line arguments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedArgs {
+    flags: Vec<String>,
+    values: HashMap<String, Vec<String>>,
+    help: bool,
+}
+
+impl ParsedArgs {
+    /// Whether the flag `name` was given
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// Last value of the option or positional argument `name`,
+    /// or its default value
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).last().map(String::as_str)
+    }
+
+    /// All values of the option or positional argument `name`
+    pub fn values(&self, name: &str) -> &[String] {
+        self.values.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Whether `--help` or `-h` was given
+    pub fn help_requested(&self) -> bool {
+        self.help
+    }
+}
+
+/// Provides a builder pattern for declaring and parsing
+/// command-line arguments.
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Debug, Clone)]
+pub struct ArgsParser {
+    pub name: String,
+    pub about: Option<String>,
+    switches: Vec<Switch>,
+    positionals: Vec<Positional>,
+}
+
+impl ArgsParser {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            about: None,
+            switches: vec![],
+            positionals: vec![],
+        }
+    }
+
+    /// Description shown at the top of the help text
+    pub fn about(mut self, about: &str) -> Self {
+        self.about = Some(about.to_string());
+        self
+    }
+
+    /// A switch without value: `--name` or `-s`
+    pub fn flag(mut self, name: &str, short: Option<char>, help: &str) -> Self {
+        self.switches.push(Switch {
+            name: name.to_string(),
+            short,
+            value_name: None,
+            help: help.to_string(),
+            default: None,
+        });
+        self
+    }
+
+    /// A switch with a value: `--name value`, `--name=value` or `-s value`.
+    /// May be given several times, see [`ParsedArgs::values()`].
+    pub fn option(mut self, name: &str, short: Option<char>, value_name: &str, help: &str) -> Self {
+        self.switches.push(Switch {
+            name: name.to_string(),
+            short,
+            value_name: Some(value_name.to_string()),
+            help: help.to_string(),
+            default: None,
+        });
+        self
+    }
+
+    /// Default value of the option `name` used when it is not given
+    pub fn default_value(mut self, name: &str, value: &str) -> Self {
+        if let Some(switch) = self.switches.iter_mut().find(|switch| switch.name == name) {
+            switch.default = Some(value.to_string());
+        }
+        self
+    }
+
+    /// A positional argument. Positional arguments are assigned in
+    /// declaration order.
+    pub fn positional(mut self, name: &str, help: &str, arity: Arity) -> Self {
+        self.positionals.push(Positional {
+            name: name.to_string(),
+            help: help.to_string(),
+            arity,
+        });
+        self
+    }
+
+    fn find_long(&self, name: &str) -> Result<&Switch> {
+        self.switches
+            .iter()
+            .find(|switch| switch.name == name)
+            .ok_or_else(|| format!("unknown option `--{}`", name).into())
+    }
+
+    fn find_short(&self, short: char) -> Result<&Switch> {
+        self.switches
+            .iter()
+            .find(|switch| switch.short == Some(short))
+            .ok_or_else(|| format!("unknown option `-{}`", short).into())
+    }
+
+    /// Parse `args`, which must not include the executable path.
+    /// If `--help` or `-h` is given, parsing stops and
+    /// [`ParsedArgs::help_requested()`] returns `true`.
+    pub fn parse(&self, args: &[String]) -> Result<ParsedArgs> {
+        let mut parsed = ParsedArgs::default();
+        let mut positionals = Vec::new();
+        let mut positional_only = false;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if positional_only || arg == "-" || !arg.starts_with('-') {
+                positionals.push(arg.clone());
+                continue;
+            }
+            if arg == "--" {
+                positional_only = true;
+                continue;
+            }
+            if arg == "--help" || arg == "-h" {
+                parsed.help = true;
+                return Ok(parsed);
+            }
+
+            let (switch, inline) = match arg.strip_prefix("--") {
+                Some(long) => match long.split_once('=') {
+                    Some((name, value)) => (self.find_long(name)?, Some(value.to_string())),
+                    None => (self.find_long(long)?, None),
+                },
+                None => {
+                    let mut chars = arg[1..].chars();
+                    let short = chars.next().unwrap_or_default();
+                    let rest: String = chars.collect();
+                    let inline = if rest.is_empty() { None } else { Some(rest) };
+                    (self.find_short(short)?, inline)
+                }
+            };
+
+            match switch.value_name {
+                Some(_) => {
+                    let value = match inline {
+                        Some(value) => value,
+                        None => args.next().cloned().ok_or_else(|| {
+                            format!("option `--{}` requires a value", switch.name)
+                        })?,
+                    };
+                    parsed
+                        .values
+                        .entry(switch.name.clone())
+                        .or_default()
+                        .push(value);
+                }
+                None => {
+                    if inline.is_some() {
+                        return Err(
+                            format!("flag `--{}` does not take a value", switch.name).into()
+                        );
+                    }
+                    parsed.flags.push(switch.name.clone());
+                }
+            }
+        }
+
+        for switch in self.switches.iter() {
+            if let Some(default) = switch.default.as_ref() {
+                parsed
+                    .values
+                    .entry(switch.name.clone())
+                    .or_insert_with(|| vec![default.clone()]);
+            }
+        }
+
+        let mut positionals = positionals.into_iter();
+        for positional in self.positionals.iter() {
+            let values: Vec<String> = match positional.arity {
+                Arity::Required => match positionals.next() {
+                    Some(value) => vec![value],
+                    None => return Err(format!("missing argument <{}>", positional.name).into()),
+                },
+                Arity::Optional => positionals.next().into_iter().collect(),
+                Arity::Many => positionals.by_ref().collect(),
+            };
+            if !values.is_empty() {
+                parsed.values.insert(positional.name.clone(), values);
+            }
+        }
+        if let Some(extra) = positionals.next() {
+            return Err(format!("unexpected argument `{}`", extra).into());
+        }
+
+        Ok(parsed)
+    }
+
+    fn usage(&self) -> String {
+        let mut usage = self.name.clone();
+        if !self.switches.is_empty() {
+            usage.push_str(" [OPTIONS]");
+        }
+        for positional in self.positionals.iter() {
+            usage.push(' ');
+            usage.push_str(&match positional.arity {
+                Arity::Required => format!("<{}>", positional.name),
+                Arity::Optional => format!("[{}]", positional.name),
+                Arity::Many => format!("[{}...]", positional.name),
+            });
+        }
+        usage
+    }
+
+    /// Help text listing all declared arguments
+    pub fn help(&self) -> String {
+        let mut options: Vec<(String, String)> = self
+            .switches
+            .iter()
+            .map(|switch| {
+                let short = match switch.short {
+                    Some(short) => format!("-{}, ", short),
+                    None => "    ".to_string(),
+                };
+                let value = match switch.value_name.as_ref() {
+                    Some(value_name) => format!(" <{}>", value_name),
+                    None => String::new(),
+                };
+                let help = match switch.default.as_ref() {
+                    Some(default) => format!("{} [default: {}]", switch.help, default),
+                    None => switch.help.clone(),
+                };
+                (format!("{}--{}{}", short, switch.name, value), help)
+            })
+            .collect();
+        options.push(("-h, --help".to_string(), "Print help".to_string()));
+
+        let args: Vec<(String, String)> = self
+            .positionals
+            .iter()
+            .map(|positional| (format!("<{}>", positional.name), positional.help.clone()))
+            .collect();
+
+        let width = options
+            .iter()
+            .chain(args.iter())
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut help = String::new();
+        if let Some(about) = self.about.as_ref() {
+            help.push_str(about);
+            help.push_str("\n\n");
+        }
+        help.push_str(&format!("Usage: {}\n", self.usage()));
+        if !args.is_empty() {
+            help.push_str("\nArguments:\n");
+            for (name, text) in args.iter() {
+                help.push_str(&format!("    {:width$}  {}\n", name, text, width = width));
+            }
+        }
+        help.push_str("\nOptions:\n");
+        for (name, text) in options.iter() {
+            help.push_str(&format!("    {:width$}  {}\n", name, text, width = width));
+        }
+        help
+    }
+}
+
+/// Types that can be created from command-line arguments.
+///
+/// For usage example please refer to [Examples](self)
+pub trait FromArgs: Sized {
+    /// The parser declaring the accepted arguments
+    fn parser() -> ArgsParser;
+
+    /// Create the value from the parsed arguments
+    fn from_args(args: &ParsedArgs) -> Result<Self>;
+
+    /// Parse `args` and create the value. Returns an error carrying the
+    /// help text if `--help` is given, or the parse error followed by
+    /// the usage line.
+    fn from_argv(args: &[String]) -> Result<Self> {
+        let parser = Self::parser();
+        let parsed = match parser.parse(args) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                return Err(format!("{}\n\nUsage: {}", String::from(err), parser.usage()).into())
+            }
+        };
+        if parsed.help_requested() {
+            return Err(parser.help().into());
+        }
+        Self::from_args(&parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn parser() -> ArgsParser {
+        ArgsParser::new("my-app")
+            .about("Edits documents")
+            .flag("verbose", Some('v'), "Print diagnostic output")
+            .option("profile", Some('p'), "NAME", "Profile to use")
+            .default_value("profile", "default")
+            .option("tag", None, "TAG", "Tag to apply")
+            .positional("mode", "Mode to start in", Arity::Required)
+            .positional("files", "Documents to open", Arity::Many)
+    }
+
+    fn error(args: &[&str]) -> String {
+        String::from(parser().parse(&argv(args)).unwrap_err())
+    }
+
+    struct Options {
+        verbose: bool,
+        profile: String,
+        files: Vec<String>,
+    }
+
+    impl FromArgs for Options {
+        fn parser() -> ArgsParser {
+            ArgsParser::new("my-app")
+                .flag("verbose", Some('v'), "Print diagnostic output")
+                .option("profile", Some('p'), "NAME", "Profile to use")
+                .default_value("profile", "default")
+                .positional("files", "Documents to open", Arity::Many)
+        }
+
+        fn from_args(args: &ParsedArgs) -> Result<Self> {
+            Ok(Self {
+                verbose: args.flag("verbose"),
+                profile: args.value("profile").unwrap_or_default().to_string(),
+                files: args.values("files").to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let parsed = parser().parse(&argv(&["-v", "edit"])).unwrap();
+        assert!(parsed.flag("verbose"));
+        let parsed = parser().parse(&argv(&["edit", "--verbose"])).unwrap();
+        assert!(parsed.flag("verbose"));
+        let parsed = parser().parse(&argv(&["edit"])).unwrap();
+        assert!(!parsed.flag("verbose"));
+        assert_eq!(
+            error(&["--verbose=yes", "edit"]),
+            "flag `--verbose` does not take a value"
+        );
+    }
+
+    #[test]
+    fn options() {
+        let parsed = parser()
+            .parse(&argv(&[
+                "--profile",
+                "work",
+                "--tag=a",
+                "--tag",
+                "b",
+                "edit",
+            ]))
+            .unwrap();
+        assert_eq!(parsed.value("profile"), Some("work"));
+        assert_eq!(parsed.values("tag"), ["a", "b"]);
+
+        let parsed = parser().parse(&argv(&["-p", "home", "edit"])).unwrap();
+        assert_eq!(parsed.value("profile"), Some("home"));
+        let parsed = parser().parse(&argv(&["-phome", "edit"])).unwrap();
+        assert_eq!(parsed.value("profile"), Some("home"));
+        let parsed = parser().parse(&argv(&["--tag=", "edit"])).unwrap();
+        assert_eq!(parsed.value("tag"), Some(""));
+
+        let parsed = parser().parse(&argv(&["edit"])).unwrap();
+        assert_eq!(parsed.value("profile"), Some("default"));
+        assert_eq!(parsed.value("tag"), None);
+        assert!(parsed.values("tag").is_empty());
+
+        assert_eq!(error(&["edit", "--tag"]), "option `--tag` requires a value");
+    }
+
+    #[test]
+    fn positionals() {
+        let parsed = parser()
+            .parse(&argv(&["edit", "a.txt", "-", "-v", "b.txt"]))
+            .unwrap();
+        assert_eq!(parsed.value("mode"), Some("edit"));
+        assert_eq!(parsed.values("files"), ["a.txt", "-", "b.txt"]);
+        assert!(parsed.flag("verbose"));
+
+        let parsed = parser().parse(&argv(&["edit"])).unwrap();
+        assert!(parsed.values("files").is_empty());
+
+        let parser = ArgsParser::new("my-app")
+            .positional("input", "Input file", Arity::Required)
+            .positional("output", "Output file", Arity::Optional);
+        let parsed = parser.parse(&argv(&["in.txt"])).unwrap();
+        assert_eq!(parsed.value("output"), None);
+        assert_eq!(
+            String::from(parser.parse(&argv(&["a", "b", "c"])).unwrap_err()),
+            "unexpected argument `c`"
+        );
+    }
+
+    #[test]
+    fn missing_required() {
+        assert_eq!(error(&[]), "missing argument <mode>");
+        assert_eq!(error(&["-v"]), "missing argument <mode>");
+    }
+
+    #[test]
+    fn unknown_options() {
+        assert_eq!(error(&["--force", "edit"]), "unknown option `--force`");
+        assert_eq!(error(&["--force=1", "edit"]), "unknown option `--force`");
+        assert_eq!(error(&["-x", "edit"]), "unknown option `-x`");
+    }
+
+    #[test]
+    fn separator() {
+        let parsed = parser()
+            .parse(&argv(&["-v", "--", "--edit", "-p", "--"]))
+            .unwrap();
+        assert!(parsed.flag("verbose"));
+        assert_eq!(parsed.value("mode"), Some("--edit"));
+        assert_eq!(parsed.values("files"), ["-p", "--"]);
+        assert_eq!(parsed.value("profile"), Some("default"));
+    }
+
+    #[test]
+    fn help() {
+        let parsed = parser().parse(&argv(&["--unknown", "-h"]));
+        assert_eq!(
+            String::from(parsed.unwrap_err()),
+            "unknown option `--unknown`"
+        );
+        let parsed = parser()
+            .parse(&argv(&["-v", "--help", "--unknown"]))
+            .unwrap();
+        assert!(parsed.help_requested());
+
+        assert_eq!(
+            parser().help(),
+            "Edits documents\n\
+             \n\
+             Usage: my-app [OPTIONS] <mode> [files...]\n\
+             \n\
+             Arguments:\n    \
+             <mode>                Mode to start in\n    \
+             <files>               Documents to open\n\
+             \n\
+             Options:\n    \
+             -v, --verbose         Print diagnostic output\n    \
+             -p, --profile <NAME>  Profile to use [default: default]\n    \
+             \x20   --tag <TAG>       Tag to apply\n    \
+             -h, --help            Print help\n"
+        );
+    }
+
+    #[test]
+    fn from_argv() {
+        let options = Options::from_argv(&argv(&["-v", "a.txt", "b.txt"])).unwrap();
+        assert!(options.verbose);
+        assert_eq!(options.profile, "default");
+        assert_eq!(options.files, ["a.txt", "b.txt"]);
+
+        let err = String::from(Options::from_argv(&argv(&["--help"])).err().unwrap());
+        assert_eq!(err, Options::parser().help());
+        assert!(err.starts_with("Usage: my-app [OPTIONS] [files...]\n"));
+
+        let err = String::from(Options::from_argv(&argv(&["--force"])).err().unwrap());
+        assert_eq!(
+            err,
+            "unknown option `--force`\n\nUsage: my-app [OPTIONS] [files...]"
+        );
+    }
+}
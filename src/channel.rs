@@ -0,0 +1,293 @@
+//!
+//! Typed messaging between application windows.
+//!
+//! All windows of an NW application share the Node.js `global` object.
+//! [`Channel`] uses it to deliver serde-serialized messages to subscribers
+//! in any window, while [`RequestChannel`] lets one window answer requests
+//! made by other windows. Subscriptions of a window are removed
+//! automatically when that window closes.
+//!
+//! # Synopsis
+//! ```rust
+//! #[derive(Serialize, Deserialize)]
+//! struct ToolSelected { tool: String }
+//!
+//! // in the tool palette window
+//! let tools = Channel::<ToolSelected>::new("tools");
+//! tools.publish(&ToolSelected { tool: "brush".into() })?;
+//!
+//! // in the main window
+//! let subscription = Channel::<ToolSelected>::new("tools").subscribe(|msg| {
+//!     log_info!("selected tool: {}", msg.tool);
+//!     Ok(())
+//! })?;
+//!
+//! // request/response
+//! let documents = RequestChannel::<String, Vec<String>>::new("documents");
+//! // main window answers
+//! documents.handle(|filter| Ok(find_documents(&filter)))?;
+//! // any other window asks
+//! let list = documents.request(&"*.txt".to_string())?;
+//!
+//! subscription.unsubscribe()?;
+//! ```
+//!
+
+use crate::application::app;
+use crate::global::{get_or_create_object, get_or_create_object_in};
+use crate::window::{WindowEvent, WindowHandle};
+use js_sys::{Array, Function, Object, Reflect};
+use nw_sys::result::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use workflow_log::log_error;
+use workflow_wasm::prelude::*;
+
+const REGISTRY: &str = "__workflow_nw_channels";
+const SUBSCRIBERS: &str = "subscribers";
+const RESPONDERS: &str = "responders";
+
+fn section(kind: &str) -> Result<Object> {
+    get_or_create_object_in(&get_or_create_object(REGISTRY)?, kind)
+}
+
+fn subscribers(name: &str) -> Result<Array> {
+    let section = section(SUBSCRIBERS)?;
+    let list = Reflect::get(&section, &JsValue::from(name))?;
+    if Array::is_array(&list) {
+        return Ok(list.unchecked_into());
+    }
+    let list = Array::new();
+    Reflect::set(&section, &JsValue::from(name), &list)?;
+    Ok(list)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|err| format!("channel serialize error: {}", err).into())
+}
+
+fn from_json<T: DeserializeOwned>(text: &str) -> Result<T> {
+    serde_json::from_str(text).map_err(|err| format!("channel deserialize error: {}", err).into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SubscriptionKind {
+    Subscriber,
+    Responder,
+}
+
+/// Handle of a channel subscription or request handler.
+/// The subscription stays active until [`unsubscribe()`](Self::unsubscribe)
+/// is called or the window that created it is closed.
+pub struct Subscription {
+    kind: SubscriptionKind,
+    name: String,
+    function: Function,
+    callback_id: CallbackId,
+    closed_id: CallbackId,
+}
+
+impl Subscription {
+    fn create(
+        kind: SubscriptionKind,
+        name: &str,
+        callback: Callback<CallbackClosure<JsValue>>,
+    ) -> Result<Self> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let function: &Function = callback.as_ref();
+        let function = function.clone();
+        let callback_id = callback.get_id();
+        app.callbacks.retain(callback)?;
+
+        let name_ = name.to_string();
+        let function_ = function.clone();
+        let closed_id = WindowHandle::current().on(WindowEvent::Closed, move |_| {
+            detach(kind, &name_, &function_)?;
+            Ok(())
+        })?;
+
+        Ok(Self {
+            kind,
+            name: name.to_string(),
+            function,
+            callback_id,
+            closed_id,
+        })
+    }
+
+    /// Channel name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Stop receiving messages or requests
+    pub fn unsubscribe(self) -> Result<()> {
+        detach(self.kind, &self.name, &self.function)?;
+        WindowHandle::current().off(WindowEvent::Closed, &self.closed_id)?;
+        if let Some(app) = app() {
+            app.callbacks.remove(&self.callback_id)?;
+        }
+        Ok(())
+    }
+}
+
+fn detach(kind: SubscriptionKind, name: &str, function: &Function) -> Result<()> {
+    match kind {
+        SubscriptionKind::Subscriber => {
+            let list = subscribers(name)?;
+            let index = list.index_of(function, 0);
+            if index >= 0 {
+                let splice: Function =
+                    Reflect::get(&list, &JsValue::from("splice"))?.unchecked_into();
+                splice.call2(&list, &JsValue::from(index), &JsValue::from(1))?;
+            }
+        }
+        SubscriptionKind::Responder => {
+            let section = section(RESPONDERS)?;
+            let current = Reflect::get(&section, &JsValue::from(name))?;
+            let function: &JsValue = function.as_ref();
+            if &current == function {
+                Reflect::delete_property(&section, &JsValue::from(name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Named publish/subscribe channel carrying messages of type `T`.
+///
+/// For usage example please refer to [Examples](self)
+pub struct Channel<T> {
+    name: String,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Channel<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Channel name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Deliver `message` to all subscribers in all windows
+    pub fn publish(&self, message: &T) -> Result<()> {
+        let text = JsValue::from(to_json(message)?);
+        // iterate over a copy so that subscribers can unsubscribe while handling a message
+        let list = subscribers(&self.name)?.slice(0, u32::MAX);
+        for function in list.iter() {
+            if let Ok(function) = function.dyn_into::<Function>() {
+                if let Err(err) = function.call1(&JsValue::UNDEFINED, &text) {
+                    log_error!("channel `{}` subscriber error: {:?}", self.name, err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive messages published on this channel
+    pub fn subscribe<F>(&self, mut callback: F) -> Result<Subscription>
+    where
+        F: FnMut(T) -> Result<()> + 'static,
+    {
+        let callback = Callback::new(move |text: JsValue| -> std::result::Result<(), JsValue> {
+            let text = text.as_string().unwrap_or_default();
+            callback(from_json(&text)?)?;
+            Ok(())
+        });
+
+        subscribers(&self.name)?.push(callback.as_ref());
+        Subscription::create(SubscriptionKind::Subscriber, &self.name, callback)
+    }
+}
+
+/// Named request/response channel. One window registers a handler with
+/// [`handle()`](Self::handle), any window can then call [`request()`](Self::request).
+///
+/// For usage example please refer to [Examples](self)
+pub struct RequestChannel<Req, Resp> {
+    name: String,
+    _marker: PhantomData<(Req, Resp)>,
+}
+
+impl<Req, Resp> RequestChannel<Req, Resp>
+where
+    Req: Serialize + DeserializeOwned + 'static,
+    Resp: Serialize + DeserializeOwned + 'static,
+{
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Channel name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Answer requests made on this channel. Replaces any handler
+    /// previously registered by this or another window.
+    pub fn handle<F>(&self, mut handler: F) -> Result<Subscription>
+    where
+        F: FnMut(Req) -> Result<Resp> + 'static,
+    {
+        let callback = Callback::new(
+            move |envelope: JsValue| -> std::result::Result<(), JsValue> {
+                let text = Reflect::get(&envelope, &JsValue::from("request"))?
+                    .as_string()
+                    .unwrap_or_default();
+                let response: std::result::Result<Resp, String> = from_json(&text)
+                    .and_then(&mut handler)
+                    .map_err(String::from);
+                let text = to_json(&response)?;
+                Reflect::set(&envelope, &JsValue::from("response"), &JsValue::from(text))?;
+                Ok(())
+            },
+        );
+
+        Reflect::set(
+            &section(RESPONDERS)?,
+            &JsValue::from(self.name.as_str()),
+            callback.as_ref(),
+        )?;
+        Subscription::create(SubscriptionKind::Responder, &self.name, callback)
+    }
+
+    /// Send `request` to the window handling this channel and return its response
+    pub fn request(&self, request: &Req) -> Result<Resp> {
+        let handler = Reflect::get(&section(RESPONDERS)?, &JsValue::from(self.name.as_str()))?;
+        let handler = handler
+            .dyn_into::<Function>()
+            .map_err(|_| format!("channel `{}` has no request handler", self.name))?;
+
+        let envelope = Object::new();
+        Reflect::set(
+            &envelope,
+            &JsValue::from("request"),
+            &JsValue::from(to_json(request)?),
+        )?;
+        handler.call1(&JsValue::UNDEFINED, &envelope)?;
+
+        let text = Reflect::get(&envelope, &JsValue::from("response"))?
+            .as_string()
+            .ok_or_else(|| format!("channel `{}` handler returned no response", self.name))?;
+        let response: std::result::Result<Resp, String> = from_json(&text)?;
+        Ok(response?)
+    }
+}
@@ -0,0 +1,519 @@
+//!
+//! Reading and writing the system clipboard in multiple formats.
+//!
+//! Images are exchanged as encoded PNG or JPEG bytes; the base64
+//! encoding expected by `nw.Clipboard` is handled internally.
+//!
+//! # Synopsis
+//! ```rust
+//! // copy rich text with a plain text fallback
+//! clipboard::set(&[
+//!     ClipboardData::Html("<b>bold</b> text".into()),
+//!     ClipboardData::Text("bold text".into()),
+//! ]);
+//!
+//! // copy an image
+//! let png = WindowHandle::current()
+//!     .capture_page(CaptureFormat::Png, None, None)
+//!     .await?;
+//! clipboard::set(&[ClipboardData::Png(png)]);
+//!
+//! // paste the richest format available
+//! let types = clipboard::read_available_types();
+//! for data_type in [ClipboardType::Html, ClipboardType::Png, ClipboardType::Text] {
+//!     if types.contains(&data_type) {
+//!         match clipboard::read(data_type)? {
+//!             Some(ClipboardData::Html(html)) => paste_html(&html)?,
+//!             Some(ClipboardData::Png(bytes)) => paste_image(&bytes)?,
+//!             Some(ClipboardData::Text(text)) => paste_text(&text)?,
+//!             _ => continue,
+//!         }
+//!         break;
+//!     }
+//! }
+//!
+//! // watch for copied links, keeping the last 20 distinct entries
+//! let watcher = ClipboardWatcherBuilder::new()
+//!     .interval(500)
+//!     .formats(&[ClipboardType::Text, ClipboardType::Html])
+//!     .history(20)
+//!     .on_change(|change| {
+//!         if let Some(text) = change.text() {
+//!             if text.starts_with("https://") {
+//!                 offer_paste_link(text)?;
+//!             }
+//!         }
+//!         Ok(())
+//!     })
+//!     .build()?;
+//!
+//! for entry in watcher.history()? {
+//!     log_info!("{:?}", entry.text());
+//! }
+//!
+//! // stops automatically on `Application::quit()` and when the last
+//! // handle is dropped, or explicitly
+//! watcher.stop()?;
+//! ```
+//!
+
+use crate::application::{app, ShutdownHookId};
+use crate::global::{clear_interval, set_interval};
+use js_sys::Uint8Array;
+use nw_sys::clipboard::{DataRead, DataWrite};
+use nw_sys::prelude::OptionsExt;
+use nw_sys::result::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::prelude::*;
+use workflow_log::log_error;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Uint8Array)]
+    type Buffer;
+
+    #[wasm_bindgen(js_namespace = Buffer, js_name = from)]
+    fn buffer_from_bytes(data: &Uint8Array) -> Buffer;
+
+    #[wasm_bindgen(js_namespace = Buffer, js_name = from)]
+    fn buffer_from_string(data: &str, encoding: &str) -> Buffer;
+
+    #[wasm_bindgen(method, js_name = toString)]
+    fn to_string_with_encoding(this: &Buffer, encoding: &str) -> String;
+}
+
+/// Format of clipboard data
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipreadavailabletypes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardType {
+    Text,
+    Html,
+    Rtf,
+    Png,
+    Jpeg,
+}
+
+impl ClipboardType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Html => "html",
+            Self::Rtf => "rtf",
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+        }
+    }
+
+    fn from_str(data_type: &str) -> Option<Self> {
+        match data_type {
+            "text" => Some(Self::Text),
+            "html" => Some(Self::Html),
+            "rtf" => Some(Self::Rtf),
+            "png" => Some(Self::Png),
+            "jpeg" => Some(Self::Jpeg),
+            _ => None,
+        }
+    }
+
+    fn is_image(&self) -> bool {
+        matches!(self, Self::Png | Self::Jpeg)
+    }
+}
+
+/// Clipboard content in one format
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClipboardData {
+    Text(String),
+    Html(String),
+    Rtf(String),
+    /// Encoded PNG image
+    Png(Vec<u8>),
+    /// Encoded JPEG image
+    Jpeg(Vec<u8>),
+}
+
+impl ClipboardData {
+    pub fn data_type(&self) -> ClipboardType {
+        match self {
+            Self::Text(_) => ClipboardType::Text,
+            Self::Html(_) => ClipboardType::Html,
+            Self::Rtf(_) => ClipboardType::Rtf,
+            Self::Png(_) => ClipboardType::Png,
+            Self::Jpeg(_) => ClipboardType::Jpeg,
+        }
+    }
+
+    fn to_data_write(&self) -> DataWrite {
+        let data = match self {
+            Self::Text(text) | Self::Html(text) | Self::Rtf(text) => text.clone(),
+            Self::Png(bytes) | Self::Jpeg(bytes) => {
+                buffer_from_bytes(&Uint8Array::from(&bytes[..])).to_string_with_encoding("base64")
+            }
+        };
+        let data_type = self.data_type();
+        DataWrite::new()
+            .data(&data)
+            .data_type(data_type.as_str())
+            .raw(data_type.is_image())
+    }
+
+    fn from_raw(data_type: ClipboardType, data: String) -> Self {
+        match data_type {
+            ClipboardType::Text => Self::Text(data),
+            ClipboardType::Html => Self::Html(data),
+            ClipboardType::Rtf => Self::Rtf(data),
+            ClipboardType::Png => Self::Png(buffer_from_string(&data, "base64").to_vec()),
+            ClipboardType::Jpeg => Self::Jpeg(buffer_from_string(&data, "base64").to_vec()),
+        }
+    }
+}
+
+/// Formats of the data currently in the clipboard
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipreadavailabletypes)
+pub fn read_available_types() -> Vec<ClipboardType> {
+    nw_sys::clipboard::get()
+        .get_available_types()
+        .iter()
+        .filter_map(|data_type| ClipboardType::from_str(data_type))
+        .collect()
+}
+
+/// Read the clipboard content in the given format,
+/// `None` if it is not available in that format
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipgettype-raw)
+pub fn read(data_type: ClipboardType) -> Result<Option<ClipboardData>> {
+    Ok(read_many(&[data_type])?.pop().flatten())
+}
+
+/// Read the clipboard content in several formats at once,
+/// in the order of `data_types`
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipgetclipboarddatalist)
+pub fn read_many(data_types: &[ClipboardType]) -> Result<Vec<Option<ClipboardData>>> {
+    let available = read_available_types();
+    let requested = data_types
+        .iter()
+        .filter(|data_type| available.contains(data_type))
+        .collect::<Vec<_>>();
+    let list = requested
+        .iter()
+        .map(|data_type| {
+            DataRead::new()
+                .data_type(data_type.as_str())
+                .raw(data_type.is_image())
+        })
+        .collect::<Vec<_>>();
+    let mut data = if list.is_empty() {
+        Vec::new()
+    } else {
+        nw_sys::clipboard::get().get_data_array(list)?
+    }
+    .into_iter();
+
+    let mut result = Vec::new();
+    for data_type in data_types {
+        if requested.contains(&data_type) {
+            let value = data.next().flatten();
+            result.push(value.map(|value| ClipboardData::from_raw(*data_type, value)));
+        } else {
+            result.push(None);
+        }
+    }
+    Ok(result)
+}
+
+/// Read the clipboard content as plain text
+pub fn read_text() -> Result<Option<String>> {
+    match read(ClipboardType::Text)? {
+        Some(ClipboardData::Text(text)) => Ok(Some(text)),
+        _ => Ok(None),
+    }
+}
+
+/// Replace the clipboard content with `data`, one entry per format.
+/// Applications pasting read the format they support best.
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipsetclipboarddatalist)
+pub fn set(data: &[ClipboardData]) {
+    let list = data.iter().map(ClipboardData::to_data_write).collect();
+    nw_sys::clipboard::get().set_data_array(list);
+}
+
+/// Replace the clipboard content with plain text
+pub fn set_text(text: &str) {
+    set(&[ClipboardData::Text(text.to_string())]);
+}
+
+/// Clear the clipboard
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Clipboard/#clipclear)
+pub fn clear() {
+    nw_sys::clipboard::get().clear();
+}
+
+/// Clipboard content seen by a [`ClipboardWatcher`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardChange {
+    /// Content in each watched format that is available
+    pub data: Vec<ClipboardData>,
+    /// Hash of `data`; equal content has an equal hash
+    pub hash: u64,
+    /// Time the change was detected, in milliseconds since the UNIX epoch
+    pub timestamp: f64,
+}
+
+impl ClipboardChange {
+    /// Content in the given format, if available
+    pub fn get(&self, data_type: ClipboardType) -> Option<&ClipboardData> {
+        self.data.iter().find(|data| data.data_type() == data_type)
+    }
+
+    /// Plain text content, if available
+    pub fn text(&self) -> Option<&str> {
+        match self.get(ClipboardType::Text) {
+            Some(ClipboardData::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+type ChangeHandler = Box<dyn FnMut(&ClipboardChange) -> Result<()>>;
+
+struct WatcherInner {
+    last_hash: Option<u64>,
+    history: VecDeque<ClipboardChange>,
+    history_size: usize,
+    interval_id: Option<JsValue>,
+    callback_id: Option<CallbackId>,
+    shutdown_hook: Option<ShutdownHookId>,
+}
+
+impl Drop for WatcherInner {
+    fn drop(&mut self) {
+        if let Some(interval_id) = self.interval_id.take() {
+            clear_interval(&interval_id);
+        }
+        let app = match app() {
+            Some(app) => app,
+            None => return,
+        };
+        if let Some(id) = self.shutdown_hook.take() {
+            let _ = app.remove_shutdown_hook(id);
+        }
+        if let Some(callback_id) = self.callback_id.take() {
+            // the last handle may be dropped while the timer callback runs
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = app.callbacks.remove(&callback_id);
+            });
+        }
+    }
+}
+
+/// Builder for a [`ClipboardWatcher`]
+///
+/// For usage example please refer to [Examples](self)
+pub struct ClipboardWatcherBuilder {
+    pub interval: u32,
+    pub formats: Vec<ClipboardType>,
+    pub history_size: usize,
+    pub handler: Option<ChangeHandler>,
+}
+
+impl Default for ClipboardWatcherBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardWatcherBuilder {
+    pub fn new() -> Self {
+        Self {
+            interval: 1000,
+            formats: vec![ClipboardType::Text],
+            history_size: 0,
+            handler: None,
+        }
+    }
+
+    /// Polling interval in milliseconds. Default is `1000`.
+    pub fn interval(mut self, interval: u32) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Formats read and compared on each poll. Default is text only;
+    /// watching images reads and hashes the whole image on each poll.
+    pub fn formats(mut self, formats: &[ClipboardType]) -> Self {
+        self.formats = formats.to_vec();
+        self
+    }
+
+    /// Keep the last `size` distinct changes in memory.
+    /// Content copied again moves to the front instead of being duplicated.
+    pub fn history(mut self, size: usize) -> Self {
+        self.history_size = size;
+        self
+    }
+
+    /// Called for every change of the clipboard content
+    pub fn on_change<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&ClipboardChange) -> Result<()> + 'static,
+    {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Start polling. The content present at this point is not reported.
+    pub fn build(self) -> Result<ClipboardWatcher> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let formats = self.formats;
+        let watcher = ClipboardWatcher {
+            inner: Arc::new(Mutex::new(WatcherInner {
+                last_hash: poll(&formats)?.map(|change| change.hash),
+                history: VecDeque::new(),
+                history_size: self.history_size,
+                interval_id: None,
+                callback_id: None,
+                shutdown_hook: None,
+            })),
+        };
+
+        // the timer and the shutdown hook must not keep the watcher alive
+        let inner = Arc::downgrade(&watcher.inner);
+        let mut handler = self.handler;
+        let callback = Callback::new(move |_: JsValue| -> std::result::Result<(), JsValue> {
+            let this = match inner.upgrade() {
+                Some(inner) => ClipboardWatcher { inner },
+                None => return Ok(()),
+            };
+            let change = match poll(&formats)? {
+                Some(change) => change,
+                None => return Ok(()),
+            };
+            if !this.record(&change)? {
+                return Ok(());
+            }
+            if let Some(handler) = handler.as_mut() {
+                if let Err(err) = handler(&change) {
+                    log_error!("clipboard watcher error: {:?}", err);
+                }
+            }
+            Ok(())
+        });
+
+        {
+            let mut inner = watcher.inner.lock()?;
+            inner.interval_id = Some(set_interval(callback.as_ref(), self.interval));
+            inner.callback_id = Some(callback.get_id());
+        }
+        app.callbacks.retain(callback)?;
+
+        let inner = Arc::downgrade(&watcher.inner);
+        let shutdown_hook = app.on_shutdown(move || {
+            let inner = inner.clone();
+            async move {
+                match inner.upgrade() {
+                    Some(inner) => ClipboardWatcher { inner }.stop(),
+                    None => Ok(()),
+                }
+            }
+        })?;
+        watcher.inner.lock()?.shutdown_hook = Some(shutdown_hook);
+
+        Ok(watcher)
+    }
+}
+
+/// Polls the clipboard and reports changes of its content.
+/// Created by [`ClipboardWatcherBuilder::build()`].
+///
+/// Clones refer to the same watcher. Polling stops on [`stop()`](Self::stop),
+/// on [`Application::quit()`](crate::application::Application::quit) and
+/// when the last handle is dropped.
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+pub struct ClipboardWatcher {
+    inner: Arc<Mutex<WatcherInner>>,
+}
+
+impl ClipboardWatcher {
+    /// Remember `change` if its content differs from the last one.
+    /// Returns `false` if the content is unchanged.
+    fn record(&self, change: &ClipboardChange) -> Result<bool> {
+        let mut inner = self.inner.lock()?;
+        if inner.last_hash == Some(change.hash) {
+            return Ok(false);
+        }
+        inner.last_hash = Some(change.hash);
+
+        if inner.history_size > 0 {
+            inner.history.retain(|entry| entry.hash != change.hash);
+            inner.history.push_front(change.clone());
+            let size = inner.history_size;
+            inner.history.truncate(size);
+        }
+        Ok(true)
+    }
+
+    /// Distinct changes seen so far, most recent first
+    pub fn history(&self) -> Result<Vec<ClipboardChange>> {
+        Ok(self.inner.lock()?.history.iter().cloned().collect())
+    }
+
+    pub fn clear_history(&self) -> Result<()> {
+        self.inner.lock()?.history.clear();
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> Result<bool> {
+        Ok(self.inner.lock()?.interval_id.is_some())
+    }
+
+    /// Stop polling and release the timer callback.
+    /// The history remains available.
+    pub fn stop(&self) -> Result<()> {
+        let (interval_id, callback_id) = {
+            let mut inner = self.inner.lock()?;
+            (inner.interval_id.take(), inner.callback_id.take())
+        };
+        if let Some(interval_id) = interval_id {
+            clear_interval(&interval_id);
+        }
+        if let (Some(callback_id), Some(app)) = (callback_id, app()) {
+            app.callbacks.remove(&callback_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the watched formats, `None` if none of them is available
+fn poll(formats: &[ClipboardType]) -> Result<Option<ClipboardChange>> {
+    let data = read_many(formats)?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if data.is_empty() {
+        return Ok(None);
+    }
+
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    Ok(Some(ClipboardChange {
+        data,
+        hash: hasher.finish(),
+        timestamp: js_sys::Date::now(),
+    }))
+}
@@ -0,0 +1,362 @@
+//!
+//! Display geometry and window placement helpers based on `nw.Screen`.
+//!
+//! All coordinates are in device independent pixels (DIP), the unit used
+//! by NW for window positions, window sizes and `nw.Screen` geometry.
+//! Placement is computed in device pixels of the target display, using its
+//! scale factor, so a window is aligned the same way on displays with
+//! fractional scale factors (e.g. 125% or 150%).
+//!
+//! # Synopsis
+//! ```rust
+//! // center a dialog on the display of its parent window
+//! let dialog = WindowBuilder::new("/root/dialog.html")
+//!     .size(400, 200)
+//!     .on_display_of(DisplaySource::Window(parent.clone()))
+//!     .build()
+//!     .await?;
+//!
+//! // place a notification panel in the bottom right corner of the primary display
+//! let panel = WindowBuilder::new("/root/panel.html")
+//!     .size(300, 100)
+//!     .on_display(DisplaySelector::Primary)
+//!     .position_anchored(Anchor::BottomRight, -16, -16)
+//!     .build()
+//!     .await?;
+//! ```
+//!
+
+use crate::window::WindowHandle;
+use nw_sys::result::Result;
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use web_sys::MouseEvent;
+use workflow_dom::utils::window;
+use workflow_wasm::prelude::*;
+
+/// Rectangle in screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// Check if the point is within the rectangle
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Center point of the rectangle
+    pub fn center(&self) -> (i32, i32) {
+        (
+            self.x + (self.width / 2) as i32,
+            self.y + (self.height / 2) as i32,
+        )
+    }
+}
+
+/// A connected display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Display {
+    pub id: u64,
+    pub scale_factor: f64,
+    /// full display area
+    pub bounds: Rect,
+    /// display area without task bars and docks
+    pub work_area: Rect,
+}
+
+/// Get currently connected displays. The primary display is listed first.
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Screen/#screenscreens)
+pub fn displays() -> Result<Vec<Display>> {
+    nw_sys::screen::init_once();
+    let displays = nw_sys::screen::screens()?
+        .into_iter()
+        .map(|screen| Display {
+            id: screen.id,
+            scale_factor: screen.scale_factor,
+            bounds: Rect {
+                x: screen.bounds.x as i32,
+                y: screen.bounds.y as i32,
+                width: screen.bounds.width as u32,
+                height: screen.bounds.height as u32,
+            },
+            work_area: Rect {
+                x: screen.work_area.x as i32,
+                y: screen.work_area.y as i32,
+                width: screen.work_area.width as u32,
+                height: screen.work_area.height as u32,
+            },
+        })
+        .collect();
+    Ok(displays)
+}
+
+/// Find the display containing the point, falling back to the nearest display
+pub fn display_at(displays: &[Display], x: i32, y: i32) -> Option<&Display> {
+    displays
+        .iter()
+        .find(|display| display.bounds.contains(x, y))
+        .or_else(|| {
+            displays.iter().min_by_key(|display| {
+                let (cx, cy) = display.bounds.center();
+                let dx = (cx - x) as i64;
+                let dy = (cy - y) as i64;
+                dx * dx + dy * dy
+            })
+        })
+}
+
+thread_local! {
+    static CURSOR: Cell<Option<(i32, i32)>> = const { Cell::new(None) };
+}
+
+/// Start recording the mouse position over the current window, used by
+/// [`DisplaySource::Cursor`]. NW provides no global cursor position, so
+/// the last position seen by this window is used.
+pub fn track_cursor() -> Result<()> {
+    if cursor_position().is_some() {
+        return Ok(());
+    }
+
+    let app = match crate::application::app() {
+        Some(app) => app,
+        None => return Err("app is not initialized".to_string().into()),
+    };
+
+    let win = window();
+    let position = (win.screen_x()?, win.screen_y()?);
+    CURSOR.with(|cursor| cursor.set(Some(position)));
+
+    let callback = callback!(
+        move |event: MouseEvent| -> std::result::Result<(), JsValue> {
+            CURSOR.with(|cursor| cursor.set(Some((event.screen_x(), event.screen_y()))));
+            Ok(())
+        }
+    );
+    win.add_event_listener_with_callback("mousemove", callback.as_ref())?;
+    app.callbacks.retain(callback)?;
+
+    Ok(())
+}
+
+/// Last known cursor position in screen coordinates, see [`track_cursor()`]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    CURSOR.with(Cell::get)
+}
+
+/// Selects a display by identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplaySelector {
+    /// The primary display
+    Primary,
+    /// Display with the given `nw.Screen` id
+    Id(u64),
+    /// Display at the given index in [`displays()`]
+    Index(usize),
+}
+
+/// Selects the display on which something currently is
+#[derive(Clone)]
+pub enum DisplaySource {
+    /// Display under the mouse cursor, see [`track_cursor()`]
+    Cursor,
+    /// Display showing the center of the window
+    Window(WindowHandle),
+}
+
+/// Display a window should be placed on
+#[derive(Clone)]
+pub enum DisplayChoice {
+    Select(DisplaySelector),
+    Of(DisplaySource),
+}
+
+impl DisplayChoice {
+    /// Find the chosen display. Falls back to the primary display
+    /// if the chosen one is not available.
+    pub fn resolve<'d>(&self, displays: &'d [Display]) -> Option<&'d Display> {
+        let display = match self {
+            Self::Select(DisplaySelector::Primary) => None,
+            Self::Select(DisplaySelector::Id(id)) => displays.iter().find(|d| d.id == *id),
+            Self::Select(DisplaySelector::Index(index)) => displays.get(*index),
+            Self::Of(DisplaySource::Cursor) => {
+                let (x, y) = cursor_position()
+                    .unwrap_or_else(|| WindowHandle::current().frame_rect().center());
+                display_at(displays, x, y)
+            }
+            Self::Of(DisplaySource::Window(window)) => {
+                let (x, y) = window.frame_rect().center();
+                display_at(displays, x, y)
+            }
+        };
+        display.or_else(|| displays.first())
+    }
+}
+
+/// Point of the display work area a window is aligned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Window size used for placement if neither the builder nor the
+/// manifest specifies one
+pub const DEFAULT_WINDOW_SIZE: (u32, u32) = (800, 600);
+
+/// Window placement resolved when the window is created
+#[derive(Clone)]
+pub struct Placement {
+    pub display: DisplayChoice,
+    pub anchor: Anchor,
+    pub offset: (i32, i32),
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            display: DisplayChoice::Select(DisplaySelector::Primary),
+            anchor: Anchor::Center,
+            offset: (0, 0),
+        }
+    }
+}
+
+impl Placement {
+    /// Compute the top-left position of a window of the given `size`
+    /// placed on `display`. The result is kept within the work area.
+    ///
+    /// `size` and the offset are in DIP; the free space is split in device
+    /// pixels of `display` and the result is converted back to DIP.
+    pub fn position_on(&self, display: &Display, size: (u32, u32)) -> (i32, i32) {
+        let scale = if display.scale_factor > 0.0 {
+            display.scale_factor
+        } else {
+            1.0
+        };
+        let to_device = |dip: i32| (dip as f64 * scale).round() as i32;
+        let to_dip = |device: i32| (device as f64 / scale).round() as i32;
+
+        let area = display.work_area;
+        let (width, height) = (size.0.min(area.width), size.1.min(area.height));
+        let free_x = to_device((area.width - width) as i32);
+        let free_y = to_device((area.height - height) as i32);
+
+        let (fx, fy) = match self.anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (free_x / 2, 0),
+            Anchor::TopRight => (free_x, 0),
+            Anchor::Left => (0, free_y / 2),
+            Anchor::Center => (free_x / 2, free_y / 2),
+            Anchor::Right => (free_x, free_y / 2),
+            Anchor::BottomLeft => (0, free_y),
+            Anchor::Bottom => (free_x / 2, free_y),
+            Anchor::BottomRight => (free_x, free_y),
+        };
+
+        let x = to_dip((fx + to_device(self.offset.0)).clamp(0, free_x)) + area.x;
+        let y = to_dip((fy + to_device(self.offset.1)).clamp(0, free_y)) + area.y;
+
+        (x, y)
+    }
+
+    /// Resolve the position of a window of the given `size` against
+    /// the currently connected displays.
+    pub fn resolve(&self, size: (u32, u32)) -> Result<Option<(i32, i32)>> {
+        let displays = displays()?;
+        Ok(self
+            .display
+            .resolve(&displays)
+            .map(|display| self.position_on(display, size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(scale_factor: f64) -> Display {
+        let area = Rect {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1000,
+        };
+        Display {
+            id: 2,
+            scale_factor,
+            bounds: area,
+            work_area: area,
+        }
+    }
+
+    fn placement(anchor: Anchor, dx: i32, dy: i32) -> Placement {
+        Placement {
+            anchor,
+            offset: (dx, dy),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn center() {
+        let placement = placement(Anchor::Center, 0, 0);
+        assert_eq!(
+            placement.position_on(&display(1.0), (400, 300)),
+            (2360, 350)
+        );
+        assert_eq!(
+            placement.position_on(&display(2.0), (400, 300)),
+            (2360, 350)
+        );
+    }
+
+    #[test]
+    fn fractional_scale_factor() {
+        // 881 DIP of free space are split as 661 device pixels at 150%
+        let placement = placement(Anchor::Center, 0, 0);
+        assert_eq!(
+            placement.position_on(&display(1.5), (399, 300)),
+            (2361, 350)
+        );
+        let placement = self::placement(Anchor::BottomRight, -16, -16);
+        assert_eq!(
+            placement.position_on(&display(1.5), (300, 100)),
+            (2884, 884)
+        );
+    }
+
+    #[test]
+    fn kept_within_work_area() {
+        let placement = placement(Anchor::TopLeft, -50, 5000);
+        assert_eq!(
+            placement.position_on(&display(1.25), (300, 100)),
+            (1920, 900)
+        );
+        let placement = self::placement(Anchor::Center, 0, 0);
+        assert_eq!(
+            placement.position_on(&display(1.0), (2000, 2000)),
+            (1920, 0)
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//!
+//! Helpers for frameless windows (`frame: false`): custom title bars,
+//! drag regions and window control buttons.
+//!
+//! # Synopsis
+//! ```rust
+//! // <div id="titlebar">
+//! //     <span class="title">My App</span>
+//! //     <input class="search">
+//! //     <button id="min">_</button>
+//! //     <button id="max">□</button>
+//! //     <button id="close">×</button>
+//! // </div>
+//!
+//! let title_bar = TitleBarBuilder::from_selector("#titlebar")?
+//!     .no_drag(".search")
+//!     .minimize_button("#min")
+//!     .maximize_button("#max")
+//!     .maximize_icons("□", "❐")
+//!     .close_button("#close")
+//!     .build()?;
+//!
+//! // or for an element of another window
+//! let element = win.dom_window().document().unwrap().get_element_by_id("titlebar").unwrap();
+//! let title_bar = TitleBarBuilder::new(&win, element).build()?;
+//! ```
+//!
+//! Chromium does not deliver mouse events to drag regions. Double-clicking
+//! a drag region is handled by the operating system, which maximizes or
+//! restores the window according to the user's settings on Windows, macOS
+//! and most Linux window managers.
+//!
+
+use crate::application::app;
+use crate::display::{display_at, displays};
+use crate::window::{WindowEvent, WindowHandle};
+use nw_sys::{prelude::*, result::Result};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, MouseEvent};
+use workflow_dom::utils::document;
+use workflow_wasm::prelude::*;
+
+/// CSS class added to the title bar element while the window is maximized
+pub const MAXIMIZED_CLASS: &str = "maximized";
+
+fn set_app_region(element: &Element, region: &str) -> Result<()> {
+    match element.dyn_ref::<HtmlElement>() {
+        Some(element) => {
+            element.style().set_property("-webkit-app-region", region)?;
+            Ok(())
+        }
+        None => Err("frameless: element is not an HtmlElement"
+            .to_string()
+            .into()),
+    }
+}
+
+fn query(root: &Element, selector: &str) -> Result<Element> {
+    match root.query_selector(selector)? {
+        Some(element) => Ok(element),
+        None => Err(format!("frameless: element `{}` not found", selector).into()),
+    }
+}
+
+/// Mark `element` as a drag region: dragging it moves the window.
+pub fn drag_region(element: &Element) -> Result<()> {
+    set_app_region(element, "drag")
+}
+
+/// Exclude `element` from a surrounding drag region so that it
+/// receives mouse input.
+pub fn no_drag_region(element: &Element) -> Result<()> {
+    set_app_region(element, "no-drag")
+}
+
+/// NW has no query for the maximized state, so a window is considered
+/// maximized if it covers the work area of its display.
+fn covers_work_area(window: &WindowHandle) -> Result<bool> {
+    let rect = window.frame_rect();
+    let (x, y) = rect.center();
+    let displays = displays()?;
+    Ok(match display_at(&displays, x, y) {
+        Some(display) => {
+            let area = display.work_area;
+            rect.x <= area.x
+                && rect.y <= area.y
+                && rect.right() >= area.right()
+                && rect.bottom() >= area.bottom()
+        }
+        None => false,
+    })
+}
+
+/// Custom title bar of a frameless window created by [`TitleBarBuilder`]
+#[derive(Clone)]
+pub struct TitleBar {
+    window: WindowHandle,
+    element: Element,
+    maximized: Arc<Mutex<bool>>,
+}
+
+impl TitleBar {
+    /// The title bar element
+    pub fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Whether the window is currently maximized
+    pub fn is_maximized(&self) -> bool {
+        self.maximized.lock().map(|v| *v).unwrap_or(false)
+    }
+
+    /// Maximize the window or restore it if it is maximized
+    pub fn toggle_maximize(&self) {
+        if self.is_maximized() {
+            self.window.unmaximize();
+        } else {
+            self.window.maximize();
+        }
+    }
+}
+
+/// Provides a builder pattern for turning a DOM element into the title bar
+/// of a frameless window.
+///
+/// For usage example please refer to [Examples](self)
+pub struct TitleBarBuilder {
+    pub window: WindowHandle,
+    pub element: Element,
+    pub no_drag: Vec<String>,
+    pub minimize: Option<String>,
+    pub maximize: Option<String>,
+    pub maximize_icons: Option<(String, String)>,
+    pub close: Option<String>,
+    pub double_click_maximize: bool,
+}
+
+impl TitleBarBuilder {
+    pub fn new(window: &WindowHandle, element: Element) -> Self {
+        Self {
+            window: window.clone(),
+            element,
+            no_drag: vec![],
+            minimize: None,
+            maximize: None,
+            maximize_icons: None,
+            close: None,
+            double_click_maximize: false,
+        }
+    }
+
+    /// Use the element matching `selector` in the document of the
+    /// current window as the title bar of the current window.
+    pub fn from_selector(selector: &str) -> Result<Self> {
+        match document().query_selector(selector)? {
+            Some(element) => Ok(Self::new(&WindowHandle::current(), element)),
+            None => Err(format!("frameless: element `{}` not found", selector).into()),
+        }
+    }
+
+    /// Exclude elements matching `selector` from the drag region,
+    /// e.g. inputs or menus placed in the title bar.
+    pub fn no_drag(mut self, selector: &str) -> Self {
+        self.no_drag.push(selector.to_string());
+        self
+    }
+
+    /// Element that minimizes the window when clicked
+    pub fn minimize_button(mut self, selector: &str) -> Self {
+        self.minimize = Some(selector.to_string());
+        self
+    }
+
+    /// Element that maximizes or restores the window when clicked
+    pub fn maximize_button(mut self, selector: &str) -> Self {
+        self.maximize = Some(selector.to_string());
+        self
+    }
+
+    /// HTML content of the maximize button while the window is
+    /// restored (`maximize`) and while it is maximized (`restore`)
+    pub fn maximize_icons(mut self, maximize: &str, restore: &str) -> Self {
+        self.maximize_icons = Some((maximize.to_string(), restore.to_string()));
+        self
+    }
+
+    /// Element that closes the window when clicked
+    pub fn close_button(mut self, selector: &str) -> Self {
+        self.close = Some(selector.to_string());
+        self
+    }
+
+    /// Whether double-clicking the `no-drag` parts of the title bar
+    /// (see [`no_drag()`](Self::no_drag)) maximizes or restores the window.
+    /// Default is `false`.
+    ///
+    /// Double-clicks on the drag region never reach the page; the
+    /// operating system handles them instead.
+    pub fn double_click_maximize(mut self, enable: bool) -> Self {
+        self.double_click_maximize = enable;
+        self
+    }
+
+    fn on_mouse_event<F>(&self, element: &Element, event: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut() + 'static,
+    {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let callback = callback!(move |_: MouseEvent| -> std::result::Result<(), JsValue> {
+            f();
+            Ok(())
+        });
+        element.add_event_listener_with_callback(event, callback.as_ref())?;
+        app.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<TitleBar> {
+        let maximized = covers_work_area(&self.window).unwrap_or(false);
+        let title_bar = TitleBar {
+            window: self.window.clone(),
+            element: self.element.clone(),
+            maximized: Arc::new(Mutex::new(maximized)),
+        };
+        self.element
+            .class_list()
+            .toggle_with_force(MAXIMIZED_CLASS, maximized)?;
+
+        drag_region(&self.element)?;
+        for selector in self.no_drag.iter() {
+            let list = self.element.query_selector_all(selector)?;
+            for index in 0..list.length() {
+                if let Some(element) = list.get(index).and_then(|node| node.dyn_into().ok()) {
+                    no_drag_region(&element)?;
+                }
+            }
+        }
+
+        if let Some(selector) = self.minimize.as_ref() {
+            let button = query(&self.element, selector)?;
+            no_drag_region(&button)?;
+            let window = self.window.clone();
+            self.on_mouse_event(&button, "click", move || window.minimize())?;
+        }
+
+        let maximize_button = match self.maximize.as_ref() {
+            Some(selector) => {
+                let button = query(&self.element, selector)?;
+                no_drag_region(&button)?;
+                let title_bar = title_bar.clone();
+                self.on_mouse_event(&button, "click", move || title_bar.toggle_maximize())?;
+                if let Some((maximize, restore)) = self.maximize_icons.as_ref() {
+                    button.set_inner_html(if maximized { restore } else { maximize });
+                }
+                Some(button)
+            }
+            None => None,
+        };
+
+        if let Some(selector) = self.close.as_ref() {
+            let button = query(&self.element, selector)?;
+            no_drag_region(&button)?;
+            let window = self.window.clone();
+            self.on_mouse_event(&button, "click", move || window.close())?;
+        }
+
+        if self.double_click_maximize {
+            let title_bar = title_bar.clone();
+            self.on_mouse_event(&self.element, "dblclick", move || {
+                title_bar.toggle_maximize()
+            })?;
+        }
+
+        let minimized = Arc::new(Mutex::new(false));
+        for event in [
+            WindowEvent::Maximize,
+            WindowEvent::Minimize,
+            WindowEvent::Restore,
+        ] {
+            let title_bar = title_bar.clone();
+            let minimized = minimized.clone();
+            let button = maximize_button.clone();
+            let icons = self.maximize_icons.clone();
+            self.window.on(event, move |_| {
+                let mut minimized = minimized.lock().map_err(|err| format!("{:?}", err))?;
+                let maximized = match event {
+                    WindowEvent::Minimize => {
+                        *minimized = true;
+                        return Ok(());
+                    }
+                    // restoring from minimized state keeps the maximized state
+                    WindowEvent::Restore if *minimized => {
+                        *minimized = false;
+                        return Ok(());
+                    }
+                    WindowEvent::Maximize => true,
+                    _ => false,
+                };
+
+                *title_bar
+                    .maximized
+                    .lock()
+                    .map_err(|err| format!("{:?}", err))? = maximized;
+                title_bar
+                    .element
+                    .class_list()
+                    .toggle_with_force(MAXIMIZED_CLASS, maximized)?;
+                if let (Some(button), Some((maximize, restore))) = (button.as_ref(), icons.as_ref())
+                {
+                    button.set_inner_html(if maximized { restore } else { maximize });
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(title_bar)
+    }
+}
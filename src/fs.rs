@@ -0,0 +1,110 @@
+//!
+//! Minimal bindings for the Node.js `fs` and `path` modules available
+//! in the NW context, used for persisting application data.
+//!
+
+use nw_sys::result::Result;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = require)]
+    fn require(name: &str) -> JsValue;
+
+    type NodeFs;
+
+    #[wasm_bindgen(method, catch, js_name = readFileSync)]
+    fn read_file_sync(
+        this: &NodeFs,
+        path: &str,
+        encoding: &str,
+    ) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = writeFileSync)]
+    fn write_file_sync(
+        this: &NodeFs,
+        path: &str,
+        data: &JsValue,
+    ) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = renameSync)]
+    fn rename_sync(this: &NodeFs, from: &str, to: &str) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = unlinkSync)]
+    fn unlink_sync(this: &NodeFs, path: &str) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = existsSync)]
+    fn exists_sync(this: &NodeFs, path: &str) -> bool;
+
+    #[wasm_bindgen(method, catch, js_name = mkdirSync)]
+    fn mkdir_sync(this: &NodeFs, path: &str, options: &JsValue)
+        -> std::result::Result<(), JsValue>;
+
+    type NodePath;
+
+    #[wasm_bindgen(method, js_name = join)]
+    fn join2(this: &NodePath, a: &str, b: &str) -> String;
+
+    #[wasm_bindgen(method, js_name = dirname)]
+    fn dirname(this: &NodePath, path: &str) -> String;
+}
+
+fn fs() -> NodeFs {
+    require("fs").unchecked_into()
+}
+
+fn node_path() -> NodePath {
+    require("path").unchecked_into()
+}
+
+/// Join two path segments using the platform separator
+pub fn join(a: &str, b: &str) -> String {
+    node_path().join2(a, b)
+}
+
+/// Check if the file or directory exists
+pub fn exists(path: &str) -> bool {
+    fs().exists_sync(path)
+}
+
+/// Read a UTF-8 text file
+pub fn read_to_string(path: &str) -> Result<String> {
+    let text = fs().read_file_sync(path, "utf8")?;
+    text.as_string()
+        .ok_or_else(|| format!("fs::read_to_string(): `{}` is not a text file", path).into())
+}
+
+/// Write text or binary data to the file, creating the parent directory if needed
+pub fn write(path: &str, data: &JsValue) -> Result<()> {
+    create_parent_dir(path)?;
+    fs().write_file_sync(path, data)?;
+    Ok(())
+}
+
+/// Write binary data to the file, creating the parent directory if needed
+pub fn write_bytes(path: &str, data: &[u8]) -> Result<()> {
+    write(path, &js_sys::Uint8Array::from(data).into())
+}
+
+/// Write text by writing a temporary file and renaming it over
+/// the destination, so readers never observe a partially written file.
+pub fn write_atomic(path: &str, text: &str) -> Result<()> {
+    let tmp = format!("{}.tmp", path);
+    write(&tmp, &JsValue::from(text))?;
+    if let Err(err) = fs().rename_sync(&tmp, path) {
+        let _ = fs().unlink_sync(&tmp);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+fn create_parent_dir(path: &str) -> Result<()> {
+    let dir = node_path().dirname(path);
+    if !exists(&dir) {
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"recursive".into(), &JsValue::TRUE)?;
+        fs().mkdir_sync(&dir, &options)?;
+    }
+    Ok(())
+}
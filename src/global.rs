@@ -0,0 +1,46 @@
+//!
+//! Access to the Node.js `global` object shared by all application windows.
+//!
+
+use js_sys::{Function, Object};
+use nw_sys::result::Result;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern "C" {
+    type Global;
+
+    #[wasm_bindgen(getter, static_method_of = Global, js_class = global, js_name = global)]
+    fn get_global() -> Object;
+
+    /// `setInterval()` of the global scope, returns the interval id
+    #[wasm_bindgen(js_name = setInterval)]
+    pub(crate) fn set_interval(handler: &Function, timeout: u32) -> JsValue;
+
+    /// `clearInterval()` of the global scope
+    #[wasm_bindgen(js_name = clearInterval)]
+    pub(crate) fn clear_interval(id: &JsValue);
+}
+
+/// Get the Node.js `global` object
+pub fn global() -> Object {
+    Global::get_global()
+}
+
+/// Get the object stored in `global[key]`, creating it if it does not exist.
+pub fn get_or_create_object(key: &str) -> Result<Object> {
+    get_or_create_object_in(&global(), key)
+}
+
+/// Get the object stored in `target[key]`, creating it if it does not exist.
+pub fn get_or_create_object_in(target: &Object, key: &str) -> Result<Object> {
+    let key = JsValue::from(key);
+    let value = js_sys::Reflect::get(target, &key)?;
+    if value.is_object() {
+        return Ok(value.unchecked_into());
+    }
+    let object = Object::new();
+    js_sys::Reflect::set(target, &key, &object)?;
+    Ok(object)
+}
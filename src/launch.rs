@@ -0,0 +1,331 @@
+//!
+//! Single-instance support: forwarding the command line of a second
+//! launch to the running application.
+//!
+//! NW runs applications in single-instance mode unless the manifest sets
+//! `"single-instance": false`. Launching the application again does not
+//! start a new process; instead the running instance receives the
+//! command line of the new launch through the `nw.App` `open` event.
+//! [`Application::on_launch_request()`] parses it into a [`LaunchRequest`],
+//! brings the main window to the front and calls the handler.
+//!
+//! # Synopsis
+//! ```rust
+//! let app = Application::new()?;
+//!
+//! app.on_launch_request(Some("main"), |request| {
+//!     for url in request.urls() {
+//!         open_deep_link(url)?;
+//!     }
+//!     for file in request.files() {
+//!         open_document(file)?;
+//!     }
+//!     if request.has_switch("new-window") {
+//!         open_blank_window()?;
+//!     }
+//!     Ok(())
+//! })?;
+//!
+//! // parsing is independent of NW
+//! let request = LaunchRequest::parse(r#""C:\My App\app.exe" --profile=work "C:\docs\a b.txt""#);
+//! assert_eq!(request.files(), vec![r"C:\docs\a b.txt"]);
+//! assert_eq!(request.switch("profile"), Some(Some("work")));
+//! ```
+//!
+
+use crate::application::{app, Application};
+use crate::window::WindowHandle;
+use nw_sys::result::Result;
+
+/// Switches added by Chromium/NW to the forwarded command line,
+/// not passed by the user.
+const INTERNAL_SWITCHES: &[&str] = &[
+    "original-process-start-time",
+    "nwapp",
+    "flag-switches-begin",
+    "flag-switches-end",
+];
+
+/// Command line of a second application launch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchRequest {
+    /// The unparsed command line
+    pub command_line: String,
+    /// Switches (`--name` or `--name=value`) in order of appearance
+    pub switches: Vec<(String, Option<String>)>,
+    /// Positional arguments, without the executable path
+    pub args: Vec<String>,
+    /// All arguments in order, without the executable path and switches
+    /// added by Chromium, e.g. for [`FromArgs::from_argv()`](crate::args::FromArgs::from_argv)
+    pub argv: Vec<String>,
+}
+
+impl LaunchRequest {
+    /// Parse a command line as received from the `open` event.
+    ///
+    /// Arguments are separated by whitespace and may be quoted with `"` or `'`.
+    /// Backslashes follow the Windows rules: they are kept as-is unless
+    /// followed by `"`, in which case `2n` backslashes become `n` and the
+    /// quote opens or closes a quoted part, while `2n+1` backslashes become
+    /// `n` followed by a literal `"`. As an exception, a single backslash
+    /// before a closing quote at the end of an argument is kept, so that
+    /// `"C:\dir\"` is parsed as `C:\dir\`.
+    /// Inside `'` quotes backslashes are literal. The first argument (the executable) and
+    /// switches added by Chromium are dropped. Arguments after `--`
+    /// are positional even if they start with `--`.
+    pub fn parse(command_line: &str) -> Self {
+        let mut request = LaunchRequest {
+            command_line: command_line.to_string(),
+            ..Default::default()
+        };
+
+        let mut positional_only = false;
+        for arg in split_command_line(command_line).into_iter().skip(1) {
+            if positional_only {
+                request.argv.push(arg.clone());
+                request.args.push(arg);
+                continue;
+            }
+            if arg == "--" {
+                request.argv.push(arg);
+                positional_only = true;
+                continue;
+            }
+            match arg.strip_prefix("--") {
+                Some(switch) => {
+                    let (name, value) = match switch.split_once('=') {
+                        Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                        None => (switch.to_string(), None),
+                    };
+                    if !INTERNAL_SWITCHES.contains(&name.as_str()) {
+                        request.switches.push((name, value));
+                        request.argv.push(arg);
+                    }
+                }
+                None => {
+                    request.argv.push(arg.clone());
+                    request.args.push(arg);
+                }
+            }
+        }
+
+        request
+    }
+
+    /// Whether the switch `--name` was given
+    pub fn has_switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|(switch, _)| switch == name)
+    }
+
+    /// Value of the last occurrence of the switch `--name`:
+    /// `None` if absent, `Some(None)` if given without a value.
+    pub fn switch(&self, name: &str) -> Option<Option<&str>> {
+        self.switches
+            .iter()
+            .rev()
+            .find(|(switch, _)| switch == name)
+            .map(|(_, value)| value.as_deref())
+    }
+
+    /// Positional arguments that are URLs (`scheme://...`), e.g. deep links
+    pub fn urls(&self) -> Vec<&str> {
+        self.args
+            .iter()
+            .map(String::as_str)
+            .filter(|arg| is_url(arg))
+            .collect()
+    }
+
+    /// Positional arguments that are not URLs, usually file paths
+    pub fn files(&self) -> Vec<&str> {
+        self.args
+            .iter()
+            .map(String::as_str)
+            .filter(|arg| !is_url(arg))
+            .collect()
+    }
+}
+
+fn is_url(arg: &str) -> bool {
+    match arg.split_once("://") {
+        // a single letter scheme is a Windows drive, e.g. `C://dir`
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
+
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let chars = command_line.chars().collect::<Vec<_>>();
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match quote {
+            Some('\'') if c == '\'' => quote = None,
+            Some('\'') => current.push(c),
+            _ if c == '\\' => {
+                let mut count = 1;
+                while chars.get(i) == Some(&'\\') {
+                    count += 1;
+                    i += 1;
+                }
+                in_arg = true;
+                let backslashes = if chars.get(i) != Some(&'"') {
+                    count
+                } else if count % 2 == 0 {
+                    // the quote opens or closes a quoted part
+                    count / 2
+                } else if quote.is_some()
+                    && !matches!(chars.get(i + 1), Some(c) if !c.is_whitespace())
+                {
+                    // a quoted directory ending in a backslash, e.g. `"C:\dir\"`
+                    count
+                } else {
+                    i += 1;
+                    current.push_str(&"\\".repeat(count / 2));
+                    current.push('"');
+                    continue;
+                };
+                current.push_str(&"\\".repeat(backslashes));
+            }
+            Some(_) if c == '"' => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            None if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+impl Application {
+    /// Handle launches of the application while it is already running.
+    ///
+    /// The window registered under `main_window` (see [`Application::window()`]),
+    /// or the current window if `None` or not open, is restored and focused
+    /// before `handler` is called with the parsed [`LaunchRequest`].
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#event-openargs)
+    pub fn on_launch_request<F>(&self, main_window: Option<&str>, mut handler: F) -> Result<()>
+    where
+        F: FnMut(LaunchRequest) -> Result<()> + 'static,
+    {
+        let main_window = main_window.map(String::from);
+        self.on_open(move |command_line| {
+            let window = match (main_window.as_ref(), app()) {
+                (Some(id), Some(app)) => app.window(id)?,
+                _ => None,
+            };
+            let window = window.unwrap_or_else(WindowHandle::current);
+            window.restore();
+            window.show();
+            window.focus();
+
+            handler(LaunchRequest::parse(&command_line))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(LaunchRequest::parse(""), LaunchRequest::default());
+        let request = LaunchRequest::parse("   ");
+        assert!(request.args.is_empty());
+        assert!(request.argv.is_empty());
+        let request = LaunchRequest::parse("app.exe");
+        assert!(request.args.is_empty());
+        assert!(request.switches.is_empty());
+    }
+
+    #[test]
+    fn quoted_paths() {
+        let request = LaunchRequest::parse(
+            r#""C:\Program Files\My App\app.exe" "C:\docs\a b.txt" 'd e.txt' C:\plain.txt"#,
+        );
+        assert_eq!(
+            request.files(),
+            vec![r"C:\docs\a b.txt", "d e.txt", r"C:\plain.txt"]
+        );
+        assert!(request.urls().is_empty());
+    }
+
+    #[test]
+    fn backslashes() {
+        let split = |text: &str| split_command_line(text);
+        assert_eq!(split(r"C:\dir\\server"), vec![r"C:\dir\\server"]);
+        assert_eq!(split(r#""C:\dir\" --flag"#), vec![r"C:\dir\", "--flag"]);
+        assert_eq!(split(r#""C:\dir\\" next"#), vec![r"C:\dir\", "next"]);
+        assert_eq!(split(r#""say \"hi\"""#), vec![r#"say "hi""#]);
+        assert_eq!(split(r#"a\"b"#), vec![r#"a"b"#]);
+        assert_eq!(split(r#"a\\"b c"#), vec![r"a\b c"]);
+        assert_eq!(split(r#"a\\\"b"#), vec![r#"a\"b"#]);
+        assert_eq!(split(r"'C:\dir\'"), vec![r"C:\dir\"]);
+    }
+
+    #[test]
+    fn deep_links() {
+        let request = LaunchRequest::parse(
+            "/usr/bin/app myapp://open?doc=1 https://example.com/a C://dir/file.txt",
+        );
+        assert_eq!(
+            request.urls(),
+            vec!["myapp://open?doc=1", "https://example.com/a"]
+        );
+        assert_eq!(request.files(), vec!["C://dir/file.txt"]);
+    }
+
+    #[test]
+    fn switches() {
+        let request = LaunchRequest::parse(
+            "app --nwapp=/tmp/app --new-window --profile=work --profile=home \
+             --original-process-start-time=1 file.txt -- --not-a-switch",
+        );
+        assert!(request.has_switch("new-window"));
+        assert!(!request.has_switch("nwapp"));
+        assert!(!request.has_switch("original-process-start-time"));
+        assert_eq!(request.switch("new-window"), Some(None));
+        assert_eq!(request.switch("profile"), Some(Some("home")));
+        assert_eq!(request.switch("missing"), None);
+        assert_eq!(request.args, vec!["file.txt", "--not-a-switch"]);
+        assert_eq!(
+            request.argv,
+            vec![
+                "--new-window",
+                "--profile=work",
+                "--profile=home",
+                "file.txt",
+                "--",
+                "--not-a-switch"
+            ]
+        );
+    }
+}
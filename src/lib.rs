@@ -89,10 +89,30 @@
 //! ```
 
 pub mod application;
+pub mod args;
+pub mod channel;
+pub mod clipboard;
+pub mod display;
+pub mod frameless;
+mod fs;
+pub mod global;
+pub mod launch;
+pub mod manifest;
 pub mod media;
 pub mod menu;
+pub mod notification;
 pub mod prelude;
+pub mod print;
+pub mod progress;
+pub mod proxy;
+pub mod session;
+pub mod settings;
 pub mod shortcut;
+pub mod state;
 pub mod tray;
+pub mod tray_status;
+pub mod window;
+pub mod window_state;
+pub mod zoom;
 
 pub use workflow_wasm::prelude::*;
@@ -0,0 +1,144 @@
+//!
+//! Typed access to the application manifest (`package.json`).
+//!
+//! # Synopsis
+//! ```rust
+//! let app = Application::new()?;
+//!
+//! // built-in manifest fields
+//! let manifest = app.manifest::<Manifest>()?;
+//! log_info!("{} {}", manifest.name, manifest.version.unwrap_or_default());
+//!
+//! // application specific section, e.g. `"updater": { "url": "..." }`
+//! #[derive(Deserialize)]
+//! struct Updater { url: String }
+//! let updater: Option<Updater> = manifest.extra("updater")?;
+//!
+//! // or a custom type covering the whole manifest
+//! let custom = app.manifest::<MyManifest>()?;
+//!
+//! // window title and icon taken from the manifest
+//! let window = WindowBuilder::new("/root/index.html")
+//!     .manifest_defaults()?
+//!     .build()
+//!     .await?;
+//! ```
+//!
+
+use crate::application::Application;
+use nw_sys::result::Result;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(thread_local_v2, js_namespace = ["nw", "App"], js_name = manifest)]
+    static MANIFEST: JsValue;
+}
+
+/// The `window` section of the manifest
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#window-subfields)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ManifestWindow {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub icon: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub min_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub position: Option<String>,
+    pub frame: Option<bool>,
+    pub resizable: Option<bool>,
+    pub show: Option<bool>,
+    pub transparent: Option<bool>,
+    pub always_on_top: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub kiosk: Option<bool>,
+}
+
+/// Application manifest
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub main: Option<String>,
+    #[serde(default)]
+    pub window: ManifestWindow,
+    #[serde(default, rename = "chromium-args")]
+    pub chromium_args: Option<String>,
+    /// URL patterns allowed to call Node.js, given as a string or an array
+    #[serde(default, rename = "node-remote", deserialize_with = "one_or_many")]
+    pub node_remote: Vec<String>,
+    #[serde(default, rename = "single-instance")]
+    pub single_instance: Option<bool>,
+    /// All other top-level fields, e.g. application specific sections
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Manifest {
+    /// Deserialize the application specific top-level field `key`
+    pub fn extra<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        match self.extra.get(key) {
+            Some(value) => {
+                let value = serde_json::from_value(value.clone())
+                    .map_err(|err| format!("manifest field `{}`: {}", key, err))?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Single-instance mode is enabled unless disabled in the manifest
+    pub fn is_single_instance(&self) -> bool {
+        self.single_instance.unwrap_or(true)
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Deserialize `nw.App.manifest` into `T`
+pub fn manifest<T: DeserializeOwned>() -> Result<T> {
+    let text = MANIFEST
+        .with(js_sys::JSON::stringify)?
+        .as_string()
+        .unwrap_or_default();
+    let manifest =
+        serde_json::from_str(&text).map_err(|err| format!("unable to parse manifest: {}", err))?;
+    Ok(manifest)
+}
+
+impl Application {
+    /// Deserialize the application manifest into [`Manifest`]
+    /// or an application specific type.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appmanifest)
+    pub fn manifest<T: DeserializeOwned>(&self) -> Result<T> {
+        manifest()
+    }
+}
@@ -0,0 +1,323 @@
+//!
+//! Builder for desktop notifications.
+//!
+//! # Synopsis
+//! ```rust
+//! // ask once, e.g. on startup
+//! if !notification::request_permission().await? {
+//!     log_info!("notifications are disabled");
+//! }
+//!
+//! // notify from background work, bringing the main window
+//! // to the front when the notification is clicked
+//! let main = app.window("main")?.unwrap();
+//! NotificationBuilder::new("Export finished")
+//!     .body("report.pdf was saved to your documents")
+//!     .icon("resources/icons/icon.png")
+//!     .tag("export")
+//!     .focus_window(&main)
+//!     .on_click(|_| {
+//!         open_document("report.pdf")?;
+//!         Ok(())
+//!     })
+//!     .on_close(|_| {
+//!         log_info!("notification closed");
+//!         Ok(())
+//!     })
+//!     .build()
+//!     .await?;
+//! ```
+//!
+
+use crate::application::app;
+use crate::window::WindowHandle;
+use js_sys::{Array, Object, Reflect};
+use nw_sys::prelude::OptionsExt;
+use nw_sys::result::Result;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Event, Notification, NotificationPermission};
+use workflow_log::log_warning;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Notification options
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/Notification#options)
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type NotificationOptions;
+}
+
+impl OptionsExt for NotificationOptions {}
+
+type EventHandler = Box<dyn FnMut(Event) -> std::result::Result<(), JsValue>>;
+
+/// Whether the user allowed the application to show notifications
+pub fn permission_granted() -> bool {
+    Notification::permission() == NotificationPermission::Granted
+}
+
+/// Ask the user for permission to show notifications unless already
+/// decided. Returns `true` if notifications are allowed.
+///
+/// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/requestPermission_static)
+pub async fn request_permission() -> Result<bool> {
+    match Notification::permission() {
+        NotificationPermission::Granted => return Ok(true),
+        NotificationPermission::Denied => return Ok(false),
+        _ => {}
+    }
+    let permission = JsFuture::from(Notification::request_permission()?).await?;
+    Ok(permission.as_string().as_deref() == Some("granted"))
+}
+
+/// Provides a builder pattern for showing a desktop notification.
+///
+/// Callbacks are retained in [`Application::callbacks`](crate::application::Application::callbacks)
+/// while the notification exists and released once it is closed.
+///
+/// For usage example please refer to [Examples](self)
+pub struct NotificationBuilder {
+    pub title: String,
+    pub options: NotificationOptions,
+    pub actions: Vec<(String, String)>,
+    pub focus_window: Option<WindowHandle>,
+    pub on_click: Option<EventHandler>,
+    pub on_close: Option<EventHandler>,
+    pub on_error: Option<EventHandler>,
+}
+
+impl NotificationBuilder {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            options: NotificationOptions::new(),
+            actions: Vec::new(),
+            focus_window: None,
+            on_click: None,
+            on_close: None,
+            on_error: None,
+        }
+    }
+
+    pub fn set(mut self, key: &str, value: JsValue) -> Self {
+        self.options = self.options.set(key, value);
+        self
+    }
+
+    /// Text shown below the title
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/body)
+    pub fn body(self, body: &str) -> Self {
+        self.set("body", JsValue::from(body))
+    }
+
+    /// Path or URL of the icon shown in the notification
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/icon)
+    pub fn icon(self, icon: &str) -> Self {
+        self.set("icon", JsValue::from(icon))
+    }
+
+    /// A new notification with the same tag replaces the previous one.
+    /// The callbacks of the replaced notification are released, as it
+    /// receives no `close` event.
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/tag)
+    pub fn tag(self, tag: &str) -> Self {
+        self.set("tag", JsValue::from(tag))
+    }
+
+    /// Show the notification without sound or vibration
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/silent)
+    pub fn silent(self, silent: bool) -> Self {
+        self.set("silent", JsValue::from(silent))
+    }
+
+    /// Keep the notification visible until the user clicks or dismisses it
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/requireInteraction)
+    pub fn require_interaction(self, require_interaction: bool) -> Self {
+        self.set("requireInteraction", JsValue::from(require_interaction))
+    }
+
+    /// Action buttons as `(action, title)` pairs.
+    ///
+    /// Chromium only shows actions for notifications created by a service
+    /// worker; elsewhere the notification is shown without them.
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/actions)
+    pub fn actions(mut self, actions: &[(&str, &str)]) -> Self {
+        self.actions = actions
+            .iter()
+            .map(|(action, title)| (action.to_string(), title.to_string()))
+            .collect();
+        self
+    }
+
+    /// Restore and focus `window` when the notification is clicked
+    pub fn focus_window(mut self, window: &WindowHandle) -> Self {
+        self.focus_window = Some(window.clone());
+        self
+    }
+
+    /// The callback function when the notification is clicked
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/click_event)
+    pub fn on_click<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(Event) -> std::result::Result<(), JsValue> + 'static,
+    {
+        self.on_click = Some(Box::new(callback));
+        self
+    }
+
+    /// The callback function when the notification is closed
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/close_event)
+    pub fn on_close<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(Event) -> std::result::Result<(), JsValue> + 'static,
+    {
+        self.on_close = Some(Box::new(callback));
+        self
+    }
+
+    /// The callback function when the notification can not be shown
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/error_event)
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(Event) -> std::result::Result<(), JsValue> + 'static,
+    {
+        self.on_error = Some(Box::new(callback));
+        self
+    }
+
+    fn create_notification(&self) -> Result<Notification> {
+        if self.actions.is_empty() {
+            return Ok(Notification::new_with_options(
+                &self.title,
+                self.options.unchecked_ref(),
+            )?);
+        }
+
+        let actions = Array::new();
+        for (action, title) in self.actions.iter() {
+            let item = Object::new();
+            Reflect::set(&item, &"action".into(), &JsValue::from(action))?;
+            Reflect::set(&item, &"title".into(), &JsValue::from(title))?;
+            actions.push(&item);
+        }
+        Reflect::set(&self.options, &"actions".into(), &actions)?;
+        match Notification::new_with_options(&self.title, self.options.unchecked_ref()) {
+            Ok(notification) => Ok(notification),
+            Err(err) => {
+                log_warning!("notification actions are not supported: {:?}", err);
+                Reflect::delete_property(&self.options, &"actions".into())?;
+                Ok(Notification::new_with_options(
+                    &self.title,
+                    self.options.unchecked_ref(),
+                )?)
+            }
+        }
+    }
+
+    /// Show the notification, asking for permission first if the user
+    /// has not decided yet. Fails if notifications are not allowed.
+    ///
+    /// ⧉ [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Notification/Notification)
+    pub async fn build(self) -> Result<Notification> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+        if !request_permission().await? {
+            return Err("notifications are not allowed".to_string().into());
+        }
+
+        let notification = self.create_notification()?;
+
+        let mut click = Callback::default();
+        let mut close = Callback::default();
+        let mut error = Callback::default();
+        let ids = vec![click.get_id(), close.get_id(), error.get_id()];
+        let tag = notification.tag().filter(|tag| !tag.is_empty());
+
+        let focus_window = self.focus_window;
+        let mut on_click = self.on_click;
+        click.set_closure(move |event: Event| -> std::result::Result<(), JsValue> {
+            if let Some(window) = focus_window.as_ref() {
+                window.restore();
+                window.show();
+                window.focus();
+            }
+            if let Some(callback) = on_click.as_mut() {
+                callback(event)?;
+            }
+            Ok(())
+        });
+
+        let mut on_close = self.on_close;
+        let close_tag = tag.clone();
+        let close_ids = ids.clone();
+        close.set_closure(move |event: Event| -> std::result::Result<(), JsValue> {
+            if let Some(callback) = on_close.as_mut() {
+                callback(event)?;
+            }
+            dispose(close_tag.as_deref(), &close_ids);
+            Ok(())
+        });
+
+        let mut on_error = self.on_error;
+        let error_tag = tag.clone();
+        let error_ids = ids.clone();
+        error.set_closure(move |event: Event| -> std::result::Result<(), JsValue> {
+            if let Some(callback) = on_error.as_mut() {
+                callback(event)?;
+            }
+            dispose(error_tag.as_deref(), &error_ids);
+            Ok(())
+        });
+
+        notification.set_onclick(Some(click.as_ref()));
+        notification.set_onclose(Some(close.as_ref()));
+        notification.set_onerror(Some(error.as_ref()));
+        app.callbacks.retain(click)?;
+        app.callbacks.retain(close)?;
+        app.callbacks.retain(error)?;
+
+        if let Some(tag) = tag {
+            let replaced = app
+                .tagged_notifications
+                .lock()?
+                .insert(tag, (notification.clone(), ids));
+            if let Some((replaced, replaced_ids)) = replaced {
+                replaced.set_onclick(None);
+                replaced.set_onclose(None);
+                replaced.set_onerror(None);
+                dispose(None, &replaced_ids);
+            }
+        }
+
+        Ok(notification)
+    }
+}
+
+/// Release the callbacks of a notification and forget it
+/// if it is still the one shown under `tag`
+fn dispose(tag: Option<&str>, ids: &[CallbackId]) {
+    if let Some(app) = app() {
+        if let (Some(tag), Ok(mut notifications)) = (tag, app.tagged_notifications.lock()) {
+            if notifications.get(tag).map(|(_, shown)| shown.as_slice()) == Some(ids) {
+                notifications.remove(tag);
+            }
+        }
+        for id in ids {
+            let _ = app.callbacks.remove(id);
+        }
+    }
+}
@@ -2,7 +2,29 @@
 //! Prelude including all public structures.
 //!
 pub use crate::application::Application;
+pub use crate::args::{ArgsParser, Arity, FromArgs, ParsedArgs};
+pub use crate::channel::{Channel, RequestChannel};
+pub use crate::clipboard::{
+    ClipboardChange, ClipboardData, ClipboardType, ClipboardWatcher, ClipboardWatcherBuilder,
+};
+pub use crate::display::{Anchor, DisplaySelector, DisplaySource};
+pub use crate::frameless::TitleBarBuilder;
+pub use crate::launch::LaunchRequest;
+pub use crate::manifest::Manifest;
 pub use crate::media::VideoConstraints;
 pub use crate::menu::{menu_separator, MenuItemBuilder, MenubarBuilder};
+pub use crate::notification::NotificationBuilder;
+pub use crate::print::{Margins, PrintOptions};
+pub use crate::progress::TaskProgress;
+pub use crate::proxy::{parse_pac_result, PacProxy, ProxyConfig, ProxyRules, ProxyServer};
+pub use crate::session::{Cookie, SetCookie};
+pub use crate::settings::{Settings, SettingsBuilder, SettingsFormat, SettingsSubscription};
 pub use crate::shortcut::ShortcutBuilder;
-pub use crate::tray::TrayMenuBuilder;
+pub use crate::state::AppState;
+pub use crate::tray::{TrayHandle, TrayMenuBuilder};
+pub use crate::tray_status::{TrayState, TrayStatus, TrayStatusBuilder};
+pub use crate::window::{
+    CaptureFormat, CloseDecision, WindowBuilder, WindowEvent, WindowEventData, WindowHandle,
+};
+pub use crate::window_state::WindowStateKeeper;
+pub use crate::zoom::{zoom_menu_items, ZoomBuilder};
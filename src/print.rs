@@ -0,0 +1,263 @@
+//!
+//! Printing and print-to-PDF.
+//!
+//! # Synopsis
+//! ```rust
+//! // export the current window to PDF
+//! let options = PrintOptions::new()
+//!     .pdf_path("/tmp/report.pdf")
+//!     .landscape(true)
+//!     .margins(Margins::Custom { top: 10, bottom: 10, left: 20, right: 20 })
+//!     .header_footer("Monthly report", "Confidential")
+//!     .page_ranges(&[(1, 3)])
+//!     .background_graphics(true);
+//!
+//! WindowHandle::current().print(&options);
+//!
+//! // print silently to the default printer
+//! let printers = get_printers().await?;
+//! if let Some(printer) = printers.iter().find(|printer| printer.is_default) {
+//!     let options = PrintOptions::new()
+//!         .printer(&printer.device_name)
+//!         .silent(true);
+//!     WindowHandle::current().print(&options);
+//! }
+//! ```
+//!
+
+use crate::application::app;
+use crate::window::{WindowExt, WindowHandle};
+use futures::channel::oneshot;
+use js_sys::{Array, Object};
+use nw_sys::prelude::OptionsExt;
+use nw_sys::result::Result;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Print options
+    ///
+    ///
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type PrintOptions;
+
+    #[wasm_bindgen(method, js_name = print)]
+    fn print_with_options(this: &WindowExt, options: &PrintOptions);
+
+    #[wasm_bindgen(method, js_name = getPrinters)]
+    fn get_printers_with_callback(this: &WindowExt, callback: &js_sys::Function);
+}
+
+/// Page margins of [`PrintOptions::margins()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Margins {
+    Default,
+    None,
+    Minimum,
+    /// Custom margins in points (1/72 inch)
+    Custom {
+        top: u32,
+        bottom: u32,
+        left: u32,
+        right: u32,
+    },
+}
+
+impl Margins {
+    fn margins_type(&self) -> u32 {
+        match self {
+            Self::Default => 0,
+            Self::None => 1,
+            Self::Minimum => 2,
+            Self::Custom { .. } => 3,
+        }
+    }
+}
+
+impl OptionsExt for PrintOptions {}
+
+impl PrintOptions {
+    /// Whether to show the print dialog. Default is `true`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn autoprint(self, autoprint: bool) -> Self {
+        self.set("autoprint", JsValue::from(autoprint))
+    }
+
+    /// Hide the flashing print preview dialog
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn silent(self, silent: bool) -> Self {
+        self.set("silent", JsValue::from(silent))
+    }
+
+    /// The device name of the printer returned by [`get_printers()`].
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn printer(self, printer: &str) -> Self {
+        self.set("printer", JsValue::from(printer))
+    }
+
+    /// Print to the PDF file at the given path instead of a printer.
+    /// The print dialog is not shown.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn pdf_path(self, path: &str) -> Self {
+        self.set("pdf_path", JsValue::from(path))
+    }
+
+    /// Whether to enable header and footer
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn header_footer_enabled(self, enabled: bool) -> Self {
+        self.set("headerFooterEnabled", JsValue::from(enabled))
+    }
+
+    /// Enable header and footer and set their text
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn header_footer(self, header: &str, footer: &str) -> Self {
+        self.header_footer_enabled(true)
+            .set("headerString", JsValue::from(header))
+            .set("footerString", JsValue::from(footer))
+    }
+
+    /// Whether to use landscape or portrait
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn landscape(self, landscape: bool) -> Self {
+        self.set("landscape", JsValue::from(landscape))
+    }
+
+    /// Page margins
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn margins(self, margins: Margins) -> Self {
+        let options = self.set("marginsType", JsValue::from(margins.margins_type()));
+        match margins {
+            Margins::Custom {
+                top,
+                bottom,
+                left,
+                right,
+            } => options
+                .set("marginsCustom.marginTop", JsValue::from(top))
+                .set("marginsCustom.marginBottom", JsValue::from(bottom))
+                .set("marginsCustom.marginLeft", JsValue::from(left))
+                .set("marginsCustom.marginRight", JsValue::from(right)),
+            _ => options,
+        }
+    }
+
+    /// Paper size by name, e.g. `"A4"` or `"Letter"`, with its
+    /// dimensions in microns.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn media_size(self, name: &str, width_microns: u32, height_microns: u32) -> Self {
+        self.set("mediaSize.name", JsValue::from(name))
+            .set("mediaSize.custom_display_name", JsValue::from(name))
+            .set("mediaSize.width_microns", JsValue::from(width_microns))
+            .set("mediaSize.height_microns", JsValue::from(height_microns))
+            .set("mediaSize.is_default", JsValue::TRUE)
+    }
+
+    /// Scale of the page in percent. Default is `100`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn scale(self, scale: u32) -> Self {
+        self.set("scaleFactor", JsValue::from(scale))
+    }
+
+    /// Pages to print as inclusive `(from, to)` ranges, starting at `1`
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn page_ranges(self, ranges: &[(u32, u32)]) -> Self {
+        let list = Array::new();
+        for (from, to) in ranges {
+            let range = Object::new();
+            let _ = js_sys::Reflect::set(&range, &"from".into(), &JsValue::from(*from));
+            let _ = js_sys::Reflect::set(&range, &"to".into(), &JsValue::from(*to));
+            list.push(&range);
+        }
+        self.set("pageRanges", list.into())
+    }
+
+    /// Whether to print CSS backgrounds
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn background_graphics(self, enabled: bool) -> Self {
+        self.set("shouldPrintBackgrounds", JsValue::from(enabled))
+    }
+
+    /// Number of copies to print
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn copies(self, copies: u32) -> Self {
+        self.set("copies", JsValue::from(copies))
+    }
+}
+
+/// Printer information returned by [`get_printers()`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Printer {
+    /// Name to use with [`PrintOptions::printer()`]
+    pub device_name: String,
+    #[serde(default)]
+    pub printer_name: String,
+    #[serde(default)]
+    pub printer_description: String,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+impl WindowHandle {
+    /// Print the web contents in the window, or write it to a PDF file
+    /// when [`PrintOptions::pdf_path()`] is set.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winprintoptions)
+    pub fn print(&self, options: &PrintOptions) {
+        self.ext().print_with_options(options);
+    }
+}
+
+/// Enumerate the printers available to the system
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wingetprinterscallback)
+pub async fn get_printers() -> Result<Vec<Printer>> {
+    let app = match app() {
+        Some(app) => app,
+        None => return Err("app is not initialized".to_string().into()),
+    };
+
+    let (sender, receiver) = oneshot::channel::<JsValue>();
+    let mut sender = Some(sender);
+
+    let mut callback = Callback::default();
+    let app_clone = app.clone();
+    let callback_id = callback.get_id();
+    callback.set_closure(move |printers: JsValue| {
+        let _ = app_clone.callbacks.remove(&callback_id);
+        if let Some(sender) = sender.take() {
+            let _ = sender.send(printers);
+        }
+    });
+
+    WindowHandle::current()
+        .ext()
+        .get_printers_with_callback(callback.as_ref());
+    app.callbacks.retain(callback)?;
+
+    let printers = receiver
+        .await
+        .map_err(|_| "get_printers(): request was cancelled".to_string())?;
+    let text = js_sys::JSON::stringify(&printers)?
+        .as_string()
+        .unwrap_or_default();
+    let printers = serde_json::from_str(&text)
+        .map_err(|err| format!("get_printers(): unable to parse printers: {}", err))?;
+    Ok(printers)
+}
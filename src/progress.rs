@@ -0,0 +1,171 @@
+//!
+//! Aggregated taskbar progress of concurrent jobs.
+//!
+//! [`TaskProgress`] combines the progress of several jobs into a single
+//! value shown on the taskbar or dock icon of a window and, optionally,
+//! in the tooltip of a tray icon. The progress bar is removed and the
+//! tooltip restored when the last job finishes.
+//!
+//! # Synopsis
+//! ```rust
+//! let tray = TrayMenuBuilder::new()
+//!     .icon("resources/icons/tray-icon@2x.png")
+//!     .tooltip("My App")
+//!     .build()?;
+//!
+//! let progress = TaskProgress::new(&WindowHandle::current())
+//!     .with_tray(&tray.tray()?, "My App");
+//!
+//! let download = progress.start("download")?;
+//! let upload = progress.start("upload")?;
+//!
+//! download.set(0.5)?; // taskbar shows 25%, tooltip "My App - download, upload: 25%"
+//! upload.set(1.0)?;
+//! upload.finish()?;   // finished jobs are dropped from the aggregate
+//! download.finish()?; // progress bar removed, tooltip restored to "My App"
+//! ```
+//!
+
+use crate::window::WindowHandle;
+use nw_sys::{prelude::*, result::Result, Tray};
+
+struct Job {
+    id: u64,
+    label: String,
+    progress: f64,
+}
+
+struct Inner {
+    next_id: u64,
+    jobs: Vec<Job>,
+}
+
+/// Aggregates progress of concurrent jobs into the taskbar progress bar
+/// of a window.
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+pub struct TaskProgress {
+    window: WindowHandle,
+    tray: Option<(Tray, String)>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl TaskProgress {
+    pub fn new(window: &WindowHandle) -> Self {
+        Self {
+            window: window.clone(),
+            tray: None,
+            inner: Arc::new(Mutex::new(Inner {
+                next_id: 0,
+                jobs: Vec::new(),
+            })),
+        }
+    }
+
+    /// Also show the progress in the tooltip of `tray`. `tooltip` is the
+    /// tooltip given to [`TrayMenuBuilder::tooltip()`](crate::tray::TrayMenuBuilder::tooltip),
+    /// restored once all jobs are finished.
+    pub fn with_tray(mut self, tray: &Tray, tooltip: &str) -> Self {
+        self.tray = Some((tray.clone(), tooltip.to_string()));
+        self
+    }
+
+    /// Register a new job with progress `0`
+    pub fn start(&self, label: &str) -> Result<JobProgress> {
+        let id = {
+            let mut inner = self.inner.lock()?;
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.jobs.push(Job {
+                id,
+                label: label.to_string(),
+                progress: 0.0,
+            });
+            id
+        };
+        self.refresh()?;
+
+        Ok(JobProgress {
+            id,
+            progress: self.clone(),
+            finished: false,
+        })
+    }
+
+    /// Average progress of all running jobs, `None` if no job is running
+    pub fn value(&self) -> Result<Option<f64>> {
+        let inner = self.inner.lock()?;
+        if inner.jobs.is_empty() {
+            return Ok(None);
+        }
+        let total: f64 = inner.jobs.iter().map(|job| job.progress).sum();
+        Ok(Some(total / inner.jobs.len() as f64))
+    }
+
+    fn update(&self, id: u64, progress: f64) -> Result<()> {
+        if let Some(job) = self.inner.lock()?.jobs.iter_mut().find(|job| job.id == id) {
+            job.progress = progress.clamp(0.0, 1.0);
+        }
+        self.refresh()
+    }
+
+    fn remove(&self, id: u64) -> Result<()> {
+        self.inner.lock()?.jobs.retain(|job| job.id != id);
+        self.refresh()
+    }
+
+    fn refresh(&self) -> Result<()> {
+        let value = self.value()?;
+        self.window.set_progress_bar(value);
+
+        if let Some((tray, tooltip)) = self.tray.as_ref() {
+            match value {
+                Some(value) => {
+                    let labels = self
+                        .inner
+                        .lock()?
+                        .jobs
+                        .iter()
+                        .map(|job| job.label.clone())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let percent = (value * 100.0).round() as u32;
+                    tray.set_tooltip(&format!("{} - {}: {}%", tooltip, labels, percent));
+                }
+                None => tray.set_tooltip(tooltip),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Progress of a single job registered with [`TaskProgress::start()`].
+/// The job is finished when this handle is dropped.
+pub struct JobProgress {
+    id: u64,
+    progress: TaskProgress,
+    finished: bool,
+}
+
+impl JobProgress {
+    /// Set the job progress within `[0, 1]`
+    pub fn set(&self, progress: f64) -> Result<()> {
+        self.progress.update(self.id, progress)
+    }
+
+    /// Remove the job from the aggregated progress
+    pub fn finish(mut self) -> Result<()> {
+        self.finished = true;
+        self.progress.remove(self.id)
+    }
+}
+
+impl Drop for JobProgress {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.progress.remove(self.id);
+        }
+    }
+}
@@ -0,0 +1,540 @@
+//!
+//! Proxy configuration of the application network stack.
+//!
+//! # Synopsis
+//! ```rust
+//! let app = Application::new()?;
+//!
+//! // all traffic through a corporate proxy, https through a separate one
+//! let config = ProxyConfig::FixedServers {
+//!     rules: ProxyRules {
+//!         default: Some(ProxyServer::parse("proxy.corp:8080")?),
+//!         https: Some(ProxyServer::parse("https://secure.corp:443")?),
+//!         ..Default::default()
+//!     },
+//!     bypass: vec![],
+//! };
+//! app.set_proxy_config(&config)?;
+//!
+//! // or a PAC script
+//! app.set_proxy_config(&ProxyConfig::PacUrl("http://wpad.corp/proxy.pac".into()))?;
+//!
+//! // resolve the proxies used for a URL
+//! for proxy in app.get_proxy_for_url("https://example.com")? {
+//!     match proxy {
+//!         PacProxy::Direct => log_info!("direct"),
+//!         PacProxy::Proxy(server) => log_info!("via {}", server),
+//!     }
+//! }
+//!
+//! // parsing works without NW
+//! let proxies = parse_pac_result("PROXY cache.corp:3128; DIRECT")?;
+//! ```
+//!
+
+use crate::application::Application;
+use nw_sys::result::Result;
+
+/// Protocol used to talk to a proxy server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyScheme {
+    Http,
+    Https,
+    Socks4,
+    Socks5,
+}
+
+impl ProxyScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Https => "https",
+            Self::Socks4 => "socks4",
+            Self::Socks5 => "socks5",
+        }
+    }
+}
+
+/// A proxy server. IPv6 hosts are stored without brackets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyServer {
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: u16,
+}
+
+impl ProxyServer {
+    /// Parse `[scheme://]host:port`. The scheme defaults to `http`,
+    /// IPv6 hosts must be enclosed in brackets, e.g. `[::1]:8080`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (scheme, address) = match text.split_once("://") {
+            Some((scheme, address)) => {
+                let scheme = match scheme.to_ascii_lowercase().as_str() {
+                    "http" => ProxyScheme::Http,
+                    "https" => ProxyScheme::Https,
+                    "socks" | "socks4" => ProxyScheme::Socks4,
+                    "socks5" => ProxyScheme::Socks5,
+                    _ => return Err(format!("unsupported proxy scheme `{}`", scheme).into()),
+                };
+                (scheme, address)
+            }
+            None => (ProxyScheme::Http, text),
+        };
+        Self::from_address(scheme, address)
+    }
+
+    fn from_address(scheme: ProxyScheme, address: &str) -> Result<Self> {
+        let (host, port) = match address.strip_prefix('[') {
+            Some(rest) => {
+                let (host, port) = rest
+                    .split_once(']')
+                    .ok_or_else(|| format!("unclosed bracket in proxy address `{}`", address))?;
+                if !host.contains(':') {
+                    return Err(format!("invalid IPv6 proxy host in `{}`", address).into());
+                }
+                let port = port
+                    .strip_prefix(':')
+                    .ok_or_else(|| format!("proxy address `{}` has no port", address))?;
+                (host, port)
+            }
+            None => {
+                let (host, port) = address
+                    .rsplit_once(':')
+                    .ok_or_else(|| format!("proxy address `{}` has no port", address))?;
+                if host.contains(':') {
+                    return Err(format!(
+                        "IPv6 proxy host in `{}` must be enclosed in brackets",
+                        address
+                    )
+                    .into());
+                }
+                (host, port)
+            }
+        };
+        let port = port
+            .parse::<u16>()
+            .map_err(|_| format!("invalid proxy port in `{}`", address))?;
+
+        let server = Self {
+            scheme,
+            host: host.to_string(),
+            port,
+        };
+        server.validate()?;
+        Ok(server)
+    }
+
+    fn is_ipv6(&self) -> bool {
+        self.host.contains(':')
+    }
+
+    /// Check that the host is not empty, contains no separators,
+    /// brackets or whitespace, that an IPv6 host only contains hex digits,
+    /// `:` and `.`, and that the port is not `0`
+    pub fn validate(&self) -> Result<()> {
+        if self.host.is_empty() {
+            return Err("proxy host is empty".to_string().into());
+        }
+        let invalid = if self.is_ipv6() {
+            !self
+                .host
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.')
+        } else {
+            self.host.chars().any(|c| {
+                c.is_whitespace() || c == ';' || c == '/' || c == '=' || c == '[' || c == ']'
+            })
+        };
+        if invalid {
+            return Err(format!("invalid proxy host `{}`", self.host).into());
+        }
+        if self.port == 0 {
+            return Err(format!("invalid proxy port for `{}`", self.host).into());
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ProxyServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_ipv6() {
+            write!(
+                f,
+                "{}://[{}]:{}",
+                self.scheme.as_str(),
+                self.host,
+                self.port
+            )
+        } else {
+            write!(f, "{}://{}:{}", self.scheme.as_str(), self.host, self.port)
+        }
+    }
+}
+
+/// Proxy servers by URL scheme
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyRules {
+    /// used for URLs without a more specific server
+    pub default: Option<ProxyServer>,
+    pub http: Option<ProxyServer>,
+    pub https: Option<ProxyServer>,
+    pub ftp: Option<ProxyServer>,
+}
+
+impl ProxyRules {
+    /// Rules in Chromium format, e.g. `http=proxy:80;https=https://secure:443`
+    pub fn to_rules_string(&self) -> String {
+        let mut rules = Vec::new();
+        if let Some(server) = self.default.as_ref() {
+            rules.push(server.to_string());
+        }
+        for (scheme, server) in [
+            ("http", &self.http),
+            ("https", &self.https),
+            ("ftp", &self.ftp),
+        ] {
+            if let Some(server) = server {
+                rules.push(format!("{}={}", scheme, server));
+            }
+        }
+        rules.join(";")
+    }
+
+    fn servers(&self) -> impl Iterator<Item = &ProxyServer> {
+        [&self.default, &self.http, &self.https, &self.ftp]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Proxy configuration applied with [`Application::set_proxy_config()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyConfig {
+    /// Connect directly, without a proxy
+    Direct,
+    /// Use the given proxy servers, except for hosts matching `bypass`
+    /// (e.g. `localhost`, `*.corp`, `10.0.0.0/8`).
+    ///
+    /// `nw.App.setProxyConfig()` takes no bypass list, so `bypass` only
+    /// takes effect when passed at startup via the `--proxy-bypass-list`
+    /// switch, see [`chromium_args()`](Self::chromium_args).
+    /// [`Application::set_proxy_config()`] fails if `bypass` is not empty.
+    FixedServers {
+        rules: ProxyRules,
+        bypass: Vec<String>,
+    },
+    /// Use the PAC script at the given URL
+    PacUrl(String),
+    /// Remove proxy rules set at runtime
+    System,
+}
+
+impl ProxyConfig {
+    /// Check the configuration before it is applied
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Direct | Self::System => Ok(()),
+            Self::FixedServers { rules, bypass } => {
+                if rules.servers().next().is_none() {
+                    return Err("no proxy server specified".to_string().into());
+                }
+                for server in rules.servers() {
+                    server.validate()?;
+                }
+                for entry in bypass.iter() {
+                    if entry.is_empty() || entry.chars().any(|c| c.is_whitespace() || c == ',') {
+                        return Err(format!("invalid proxy bypass entry `{}`", entry).into());
+                    }
+                }
+                Ok(())
+            }
+            Self::PacUrl(url) => {
+                let valid = ["http://", "https://", "file://", "data:"]
+                    .iter()
+                    .any(|prefix| url.starts_with(prefix));
+                if valid && !url.contains(char::is_whitespace) {
+                    Ok(())
+                } else {
+                    Err(format!("invalid PAC script URL `{}`", url).into())
+                }
+            }
+        }
+    }
+
+    /// Chromium switches applying this configuration at startup, e.g. for
+    /// the `chromium-args` manifest field
+    pub fn chromium_args(&self) -> Result<Vec<String>> {
+        self.validate()?;
+        let args = match self {
+            Self::Direct => vec!["--no-proxy-server".to_string()],
+            Self::FixedServers { rules, bypass } => {
+                let mut args = vec![format!("--proxy-server={}", rules.to_rules_string())];
+                if !bypass.is_empty() {
+                    args.push(format!("--proxy-bypass-list={}", bypass.join(";")));
+                }
+                args
+            }
+            Self::PacUrl(url) => vec![format!("--proxy-pac-url={}", url)],
+            Self::System => vec![],
+        };
+        Ok(args)
+    }
+}
+
+/// An entry of a PAC result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacProxy {
+    Direct,
+    Proxy(ProxyServer),
+}
+
+/// Parse a PAC-style result, e.g. `PROXY host:port; SOCKS5 host:1080; DIRECT`,
+/// as returned by [`Application::get_proxy_for_url()`]
+pub fn parse_pac_result(text: &str) -> Result<Vec<PacProxy>> {
+    let mut proxies = Vec::new();
+    for entry in text
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let mut parts = entry.split_whitespace();
+        let kind = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let address = parts.next();
+        if parts.next().is_some() {
+            return Err(format!("invalid PAC entry `{}`", entry).into());
+        }
+
+        let scheme = match (kind.as_str(), address) {
+            ("DIRECT", None) => {
+                proxies.push(PacProxy::Direct);
+                continue;
+            }
+            ("PROXY", Some(_)) => ProxyScheme::Http,
+            ("HTTPS", Some(_)) => ProxyScheme::Https,
+            ("SOCKS", Some(_)) | ("SOCKS4", Some(_)) => ProxyScheme::Socks4,
+            ("SOCKS5", Some(_)) => ProxyScheme::Socks5,
+            _ => return Err(format!("invalid PAC entry `{}`", entry).into()),
+        };
+        let server = ProxyServer::from_address(scheme, address.unwrap_or_default())?;
+        proxies.push(PacProxy::Proxy(server));
+    }
+    Ok(proxies)
+}
+
+impl Application {
+    /// Apply a proxy configuration to all network requests of the application.
+    ///
+    /// Fails for [`ProxyConfig::FixedServers`] with a `bypass` list, which
+    /// can only be applied at startup, see [`ProxyConfig::chromium_args()`].
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appsetproxyconfigconfig-pac_url)
+    pub fn set_proxy_config(&self, config: &ProxyConfig) -> Result<()> {
+        config.validate()?;
+        match config {
+            ProxyConfig::Direct => nw_sys::app::set_proxy_config("direct://", ""),
+            ProxyConfig::FixedServers { rules, bypass } => {
+                if !bypass.is_empty() {
+                    return Err(
+                        "proxy bypass list can not be applied at runtime, use `ProxyConfig::chromium_args()` at startup"
+                            .to_string()
+                            .into(),
+                    );
+                }
+                nw_sys::app::set_proxy_config(&rules.to_rules_string(), "");
+            }
+            ProxyConfig::PacUrl(url) => nw_sys::app::set_proxy_config("", url),
+            ProxyConfig::System => nw_sys::app::set_proxy_config("", ""),
+        }
+        Ok(())
+    }
+
+    /// Get the proxies used for `url`, in order of preference.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appgetproxyforurlurl)
+    pub fn get_proxy_for_url(&self, url: &str) -> Result<Vec<PacProxy>> {
+        parse_pac_result(&nw_sys::app::get_proxy_for_url(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(scheme: ProxyScheme, host: &str, port: u16) -> ProxyServer {
+        ProxyServer {
+            scheme,
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn parse_server() {
+        assert_eq!(
+            ProxyServer::parse("proxy.corp:8080").unwrap(),
+            server(ProxyScheme::Http, "proxy.corp", 8080)
+        );
+        assert_eq!(
+            ProxyServer::parse(" HTTPS://secure.corp:443 ").unwrap(),
+            server(ProxyScheme::Https, "secure.corp", 443)
+        );
+        assert_eq!(
+            ProxyServer::parse("socks://10.0.0.1:1080").unwrap(),
+            server(ProxyScheme::Socks4, "10.0.0.1", 1080)
+        );
+        assert_eq!(
+            ProxyServer::parse("socks5://[::1]:1080").unwrap(),
+            server(ProxyScheme::Socks5, "::1", 1080)
+        );
+        assert_eq!(
+            ProxyServer::parse("[fe80::1]:8080").unwrap().to_string(),
+            "http://[fe80::1]:8080"
+        );
+        assert_eq!(
+            ProxyServer::parse("proxy.corp:8080").unwrap().to_string(),
+            "http://proxy.corp:8080"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_server() {
+        for text in [
+            "proxy.corp",
+            "ftp://proxy.corp:21",
+            "proxy.corp:0",
+            "proxy.corp:65536",
+            "proxy.corp:port",
+            ":8080",
+            "proxy corp:8080",
+            "fe80::1:8080",
+            "::1",
+            "[::1]",
+            "[::1]8080",
+            "[::1:8080",
+            "[proxy.corp]:8080",
+            "[fe80::g]:8080",
+            "[]:8080",
+        ] {
+            assert!(ProxyServer::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn pac_result() {
+        assert_eq!(
+            parse_pac_result("PROXY cache.corp:3128; socks5 [::1]:1080;DIRECT;").unwrap(),
+            vec![
+                PacProxy::Proxy(server(ProxyScheme::Http, "cache.corp", 3128)),
+                PacProxy::Proxy(server(ProxyScheme::Socks5, "::1", 1080)),
+                PacProxy::Direct,
+            ]
+        );
+        assert_eq!(
+            parse_pac_result("HTTPS secure:443; SOCKS s4:1080").unwrap(),
+            vec![
+                PacProxy::Proxy(server(ProxyScheme::Https, "secure", 443)),
+                PacProxy::Proxy(server(ProxyScheme::Socks4, "s4", 1080)),
+            ]
+        );
+        assert_eq!(parse_pac_result("").unwrap(), vec![]);
+        assert_eq!(parse_pac_result(" ; ").unwrap(), vec![]);
+
+        for text in [
+            "PROXY",
+            "DIRECT host:80",
+            "PROXY a:80 b:80",
+            "FTP host:21",
+            "PROXY host",
+            "PROXY fe80::1:8080",
+        ] {
+            assert!(parse_pac_result(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn validate_config() {
+        let rules = ProxyRules {
+            default: Some(server(ProxyScheme::Http, "proxy", 8080)),
+            ..Default::default()
+        };
+        let fixed = |bypass: &[&str]| ProxyConfig::FixedServers {
+            rules: rules.clone(),
+            bypass: bypass.iter().map(|entry| entry.to_string()).collect(),
+        };
+
+        assert!(ProxyConfig::Direct.validate().is_ok());
+        assert!(ProxyConfig::System.validate().is_ok());
+        assert!(fixed(&["localhost", "*.corp", "10.0.0.0/8"])
+            .validate()
+            .is_ok());
+        assert!(fixed(&[""]).validate().is_err());
+        assert!(fixed(&["a b"]).validate().is_err());
+        assert!(fixed(&["a,b"]).validate().is_err());
+        assert!(ProxyConfig::FixedServers {
+            rules: ProxyRules::default(),
+            bypass: vec![],
+        }
+        .validate()
+        .is_err());
+        assert!(ProxyConfig::FixedServers {
+            rules: ProxyRules {
+                https: Some(server(ProxyScheme::Https, "bad host", 443)),
+                ..rules.clone()
+            },
+            bypass: vec![],
+        }
+        .validate()
+        .is_err());
+
+        assert!(ProxyConfig::PacUrl("http://wpad.corp/proxy.pac".into())
+            .validate()
+            .is_ok());
+        assert!(
+            ProxyConfig::PacUrl("data:application/x-ns-proxy-autoconfig,".into())
+                .validate()
+                .is_ok()
+        );
+        assert!(ProxyConfig::PacUrl("wpad.corp/proxy.pac".into())
+            .validate()
+            .is_err());
+        assert!(ProxyConfig::PacUrl("http://wpad.corp/a b.pac".into())
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn chromium_args() {
+        assert_eq!(
+            ProxyConfig::Direct.chromium_args().unwrap(),
+            vec!["--no-proxy-server"]
+        );
+        assert!(ProxyConfig::System.chromium_args().unwrap().is_empty());
+        assert_eq!(
+            ProxyConfig::PacUrl("http://wpad.corp/proxy.pac".into())
+                .chromium_args()
+                .unwrap(),
+            vec!["--proxy-pac-url=http://wpad.corp/proxy.pac"]
+        );
+
+        let config = ProxyConfig::FixedServers {
+            rules: ProxyRules {
+                default: Some(server(ProxyScheme::Http, "proxy", 8080)),
+                https: Some(server(ProxyScheme::Https, "::1", 443)),
+                ftp: Some(server(ProxyScheme::Socks5, "socks", 1080)),
+                ..Default::default()
+            },
+            bypass: vec!["localhost".into(), "*.corp".into()],
+        };
+        assert_eq!(
+            config.chromium_args().unwrap(),
+            vec![
+                "--proxy-server=http://proxy:8080;https=https://[::1]:443;ftp=socks5://socks:1080",
+                "--proxy-bypass-list=localhost;*.corp",
+            ]
+        );
+        assert!(ProxyConfig::PacUrl("proxy.pac".into())
+            .chromium_args()
+            .is_err());
+    }
+}
@@ -0,0 +1,321 @@
+//!
+//! Browsing data of the application: HTTP caches, cookies and
+//! cross-origin access.
+//!
+//! Cookies are accessed through the `chrome.cookies` API, which requires
+//! `"permissions": ["cookies", "<all_urls>"]` in the manifest.
+//!
+//! # Synopsis
+//! ```rust
+//! let app = Application::new()?;
+//!
+//! // logout: drop the session cookies of the backend and all caches
+//! app.clear_cookies(Some("https://api.example.com")).await?;
+//! app.clear_cache();
+//!
+//! // list and set cookies
+//! for cookie in app.cookies(Some("https://api.example.com")).await? {
+//!     log_info!("{} = {}", cookie.name, cookie.value);
+//! }
+//! let cookie = SetCookie::new("https://api.example.com", "theme", "dark")
+//!     .http_only(true)
+//!     .expiration_date(js_sys::Date::now() / 1000.0 + 86400.0);
+//! app.set_cookie(&cookie).await?;
+//! app.remove_cookie("https://api.example.com", "theme").await?;
+//!
+//! // allow pages of the app to access a local service
+//! app.add_origin_access_whitelist_entry("chrome-extension://app-id/", "http", "localhost", false);
+//! ```
+//!
+
+use crate::application::{app, Application};
+use futures::channel::oneshot;
+use js_sys::{Function, Object, Reflect};
+use nw_sys::result::Result;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["chrome", "cookies"], js_name = getAll)]
+    fn cookies_get_all(details: &JsValue, callback: &Function);
+
+    #[wasm_bindgen(js_namespace = ["chrome", "cookies"], js_name = set)]
+    fn cookies_set(details: &JsValue, callback: &Function);
+
+    #[wasm_bindgen(js_namespace = ["chrome", "cookies"], js_name = remove)]
+    fn cookies_remove(details: &JsValue, callback: &Function);
+}
+
+/// A cookie as returned by [`Application::cookies()`]
+///
+/// ⧉ [Chrome Documentation](https://developer.chrome.com/docs/extensions/reference/cookies/#type-Cookie)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    #[serde(default)]
+    pub host_only: bool,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// `no_restriction`, `lax`, `strict` or `unspecified`
+    #[serde(default)]
+    pub same_site: Option<String>,
+    /// `true` for cookies without an expiration date
+    #[serde(default)]
+    pub session: bool,
+    /// Seconds since the UNIX epoch, `None` for session cookies
+    #[serde(default)]
+    pub expiration_date: Option<f64>,
+    #[serde(default)]
+    pub store_id: Option<String>,
+}
+
+impl Cookie {
+    /// URL the cookie is associated with, as used by
+    /// [`Application::remove_cookie()`]
+    pub fn url(&self) -> String {
+        let scheme = if self.secure { "https" } else { "http" };
+        format!(
+            "{}://{}{}",
+            scheme,
+            self.domain.trim_start_matches('.'),
+            self.path
+        )
+    }
+}
+
+/// Cookie to create or overwrite with [`Application::set_cookie()`]
+///
+/// ⧉ [Chrome Documentation](https://developer.chrome.com/docs/extensions/reference/cookies/#method-set)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCookie {
+    pub url: String,
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<f64>,
+}
+
+impl SetCookie {
+    pub fn new(url: &str, name: &str, value: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Make the cookie available to subdomains of `domain`
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = Some(secure);
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = Some(http_only);
+        self
+    }
+
+    /// `no_restriction`, `lax` or `strict`
+    pub fn same_site(mut self, same_site: &str) -> Self {
+        self.same_site = Some(same_site.to_string());
+        self
+    }
+
+    /// Seconds since the UNIX epoch; without it the cookie is a session cookie
+    pub fn expiration_date(mut self, expiration_date: f64) -> Self {
+        self.expiration_date = Some(expiration_date);
+        self
+    }
+}
+
+/// Call a `chrome.cookies` function and wait for its callback
+async fn call_cookies_api<F>(name: &str, call: F) -> Result<JsValue>
+where
+    F: FnOnce(&Function),
+{
+    let app = match app() {
+        Some(app) => app,
+        None => return Err("app is not initialized".to_string().into()),
+    };
+
+    let (sender, receiver) = oneshot::channel::<std::result::Result<JsValue, String>>();
+    let mut sender = Some(sender);
+
+    let mut callback = Callback::default();
+    let app_clone = app.clone();
+    let callback_id = callback.get_id();
+    callback.set_closure(move |result: JsValue| {
+        let _ = app_clone.callbacks.remove(&callback_id);
+        // errors are reported through `chrome.runtime.lastError`,
+        // which is only set while the callback runs
+        let result = match last_error_message() {
+            Some(message) => Err(message),
+            None => Ok(result),
+        };
+        if let Some(sender) = sender.take() {
+            let _ = sender.send(result);
+        }
+    });
+
+    call(callback.as_ref());
+    app.callbacks.retain(callback)?;
+
+    let result = receiver
+        .await
+        .map_err(|_| format!("chrome.cookies.{}(): request was cancelled", name))?
+        .map_err(|message| format!("chrome.cookies.{}(): {}", name, message))?;
+    Ok(result)
+}
+
+fn last_error_message() -> Option<String> {
+    let chrome = Reflect::get(&js_sys::global(), &"chrome".into()).ok()?;
+    let runtime = Reflect::get(&chrome, &"runtime".into()).ok()?;
+    let error = Reflect::get(&runtime, &"lastError".into()).ok()?;
+    if error.is_undefined() || error.is_null() {
+        return None;
+    }
+    Reflect::get(&error, &"message".into()).ok()?.as_string()
+}
+
+fn cookie_details(url: Option<&str>, name: Option<&str>) -> JsValue {
+    let details = Object::new();
+    if let Some(url) = url {
+        let _ = Reflect::set(&details, &"url".into(), &JsValue::from(url));
+    }
+    if let Some(name) = name {
+        let _ = Reflect::set(&details, &"name".into(), &JsValue::from(name));
+    }
+    details.into()
+}
+
+fn parse_cookies<T: serde::de::DeserializeOwned>(value: &JsValue) -> Result<T> {
+    let text = js_sys::JSON::stringify(value)?
+        .as_string()
+        .unwrap_or_default();
+    let cookies =
+        serde_json::from_str(&text).map_err(|err| format!("unable to parse cookies: {}", err))?;
+    Ok(cookies)
+}
+
+impl Application {
+    /// Clear the HTTP cache in memory and on disk
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appclearcache)
+    pub fn clear_cache(&self) {
+        nw_sys::app::clear_cache();
+    }
+
+    /// Mark the application cache group specified by `manifest_url` obsolete
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appclearappcachemanifest_url)
+    pub fn clear_app_cache(&self, manifest_url: &str) {
+        nw_sys::app::clear_app_cache(manifest_url);
+    }
+
+    /// Allow pages of `source_origin` to access `destination_protocol://destination_host`
+    /// (and its subdomains if `allow_destination_subdomains` is set),
+    /// bypassing the same-origin policy.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appaddoriginaccesswhitelistentrysourceorigin-destinationprotocol-destinationhost-allowdestinationsubdomains)
+    pub fn add_origin_access_whitelist_entry(
+        &self,
+        source_origin: &str,
+        destination_protocol: &str,
+        destination_host: &str,
+        allow_destination_subdomains: bool,
+    ) {
+        nw_sys::app::add_origin_access_whitelist_entry(
+            source_origin,
+            destination_protocol,
+            destination_host,
+            allow_destination_subdomains,
+        );
+    }
+
+    /// Remove an entry added with [`Application::add_origin_access_whitelist_entry()`].
+    /// The arguments must match the ones used to add it.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/App/#appremoveoriginaccesswhitelistentrysourceorigin-destinationprotocol-destinationhost-allowdestinationsubdomains)
+    pub fn remove_origin_access_whitelist_entry(
+        &self,
+        source_origin: &str,
+        destination_protocol: &str,
+        destination_host: &str,
+        allow_destination_subdomains: bool,
+    ) {
+        nw_sys::app::remove_origin_access_whitelist_entry(
+            source_origin,
+            destination_protocol,
+            destination_host,
+            allow_destination_subdomains,
+        );
+    }
+
+    /// Cookies sent with requests to `url`, or all cookies if `None`
+    ///
+    /// ⧉ [Chrome Documentation](https://developer.chrome.com/docs/extensions/reference/cookies/#method-getAll)
+    pub async fn cookies(&self, url: Option<&str>) -> Result<Vec<Cookie>> {
+        let details = cookie_details(url, None);
+        let cookies =
+            call_cookies_api("getAll", |callback| cookies_get_all(&details, callback)).await?;
+        parse_cookies(&cookies)
+    }
+
+    /// Create or overwrite a cookie
+    ///
+    /// ⧉ [Chrome Documentation](https://developer.chrome.com/docs/extensions/reference/cookies/#method-set)
+    pub async fn set_cookie(&self, cookie: &SetCookie) -> Result<Cookie> {
+        let text = serde_json::to_string(cookie)
+            .map_err(|err| format!("unable to serialize cookie: {}", err))?;
+        let details = js_sys::JSON::parse(&text)?;
+        let cookie = call_cookies_api("set", |callback| cookies_set(&details, callback)).await?;
+        parse_cookies(&cookie)
+    }
+
+    /// Delete the cookie `name` associated with `url`
+    ///
+    /// ⧉ [Chrome Documentation](https://developer.chrome.com/docs/extensions/reference/cookies/#method-remove)
+    pub async fn remove_cookie(&self, url: &str, name: &str) -> Result<()> {
+        let details = cookie_details(Some(url), Some(name));
+        call_cookies_api("remove", |callback| cookies_remove(&details, callback)).await?;
+        Ok(())
+    }
+
+    /// Delete all cookies sent with requests to `url`, or all cookies if `None`
+    pub async fn clear_cookies(&self, url: Option<&str>) -> Result<()> {
+        for cookie in self.cookies(url).await? {
+            self.remove_cookie(&cookie.url(), &cookie.name).await?;
+        }
+        Ok(())
+    }
+}
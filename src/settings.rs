@@ -0,0 +1,460 @@
+//!
+//! Persistent user settings stored under `nw.App.dataPath`.
+//!
+//! [`Settings`] keeps a serde type in a JSON or TOML file. Files are
+//! written atomically (write to a temporary file, then rename), carry a
+//! schema version and are migrated on load by the closures registered
+//! with [`SettingsBuilder::migration()`]. Subscribers are notified of
+//! every change made through the same [`Settings`] instance.
+//!
+//! # Synopsis
+//! ```rust
+//! #[derive(Default, Clone, Serialize, Deserialize)]
+//! struct Preferences {
+//!     theme: String,
+//!     spell_check: bool,
+//! }
+//!
+//! let app = Application::new()?;
+//!
+//! // version 2 renamed `dark_mode: bool` to `theme: String`
+//! let settings = SettingsBuilder::<Preferences>::new("preferences")
+//!     .version(2)
+//!     .migration(2, |mut value| {
+//!         let dark = value["dark_mode"].as_bool().unwrap_or(false);
+//!         value["theme"] = (if dark { "dark" } else { "light" }).into();
+//!         Ok(value)
+//!     })
+//!     .build()?;
+//! app.register_settings(&settings)?;
+//!
+//! // anywhere else in this window
+//! let settings = app.settings::<Preferences>("preferences")?;
+//! // the subscriber is removed when `subscription` is dropped
+//! let subscription = settings.subscribe(|prefs| {
+//!     apply_theme(&prefs.theme);
+//!     Ok(())
+//! })?;
+//! settings.update(|prefs| prefs.theme = "dark".into())?;
+//! subscription.unsubscribe()?;
+//!
+//! // checkbox menu item bound to a setting
+//! let spell_check = settings.checkbox_menu_item(
+//!     "Check Spelling",
+//!     |prefs| prefs.spell_check,
+//!     |prefs, checked| prefs.spell_check = checked,
+//! )?;
+//! ```
+//!
+
+use crate::application::Application;
+use crate::fs;
+use crate::menu::MenuItemBuilder;
+use nw_sys::menu_item::Type as MenuItemType;
+use nw_sys::{prelude::*, result::Result, MenuItem};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Weak;
+use wasm_bindgen::prelude::*;
+use workflow_log::log_error;
+
+const VERSION_KEY: &str = "version";
+const SETTINGS_KEY: &str = "settings";
+
+/// File format of a settings file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFormat {
+    Json,
+    /// TOML can not represent `null`; skip `None` fields with
+    /// `#[serde(skip_serializing_if = "Option::is_none")]`
+    Toml,
+}
+
+impl SettingsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+
+    fn parse(&self, text: &str) -> Result<Value> {
+        let value = match self {
+            Self::Json => serde_json::from_str(text).map_err(|err| err.to_string())?,
+            Self::Toml => toml::from_str(text).map_err(|err| err.to_string())?,
+        };
+        Ok(value)
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String> {
+        let text = match self {
+            Self::Json => serde_json::to_string_pretty(value).map_err(|err| err.to_string())?,
+            Self::Toml => toml::Value::try_from(value)
+                .and_then(|value| toml::to_string_pretty(&value))
+                .map_err(|err| err.to_string())?,
+        };
+        Ok(text)
+    }
+}
+
+/// Migration of the settings value to the next schema version
+pub type Migration = Arc<dyn Fn(Value) -> Result<Value>>;
+
+/// Provides a builder pattern for loading [`Settings`].
+///
+/// For usage example please refer to [Examples](self)
+pub struct SettingsBuilder<T> {
+    pub name: String,
+    pub path: Option<String>,
+    pub format: SettingsFormat,
+    pub version: u32,
+    pub migrations: Vec<(u32, Migration)>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T> SettingsBuilder<T>
+where
+    T: Serialize + DeserializeOwned + Default + Clone + 'static,
+{
+    /// Settings stored in `{name}.json` under `nw.App.dataPath`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: None,
+            format: SettingsFormat::Json,
+            version: 1,
+            migrations: vec![],
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Store the settings in the given file instead of the data path
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    /// File format. Default is [`SettingsFormat::Json`].
+    pub fn format(mut self, format: SettingsFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Current schema version. Default is `1`.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Migrate a settings value of version `version - 1` to `version`.
+    /// Migrations run in version order when older settings are loaded.
+    pub fn migration<F>(mut self, version: u32, migration: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value> + 'static,
+    {
+        self.migrations.push((version, Arc::new(migration)));
+        self
+    }
+
+    /// Load the settings, migrating them if the file has an older version.
+    /// Defaults are used if the file does not exist.
+    pub fn build(mut self) -> Result<Settings<T>> {
+        let path = match self.path.take() {
+            Some(path) => path,
+            None => fs::join(
+                &nw_sys::app::data_path(),
+                &format!("{}.{}", self.name, self.format.extension()),
+            ),
+        };
+        self.migrations.sort_by_key(|(version, _)| *version);
+
+        let (value, migrated) = if fs::exists(&path) {
+            self.load(&path)?
+        } else {
+            (T::default(), false)
+        };
+
+        let settings = Settings {
+            name: self.name,
+            path,
+            format: self.format,
+            version: self.version,
+            value: Arc::new(Mutex::new(value)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(Mutex::new(0)),
+        };
+        if migrated {
+            settings.save()?;
+        }
+
+        Ok(settings)
+    }
+
+    fn load(&self, path: &str) -> Result<(T, bool)> {
+        let document = self
+            .format
+            .parse(&fs::read_to_string(path)?)
+            .map_err(|err| format!("settings `{}`: {}", path, String::from(err)))?;
+        // files without a version header hold version 1 settings directly
+        let (mut version, mut value) = match document.get(VERSION_KEY).and_then(Value::as_u64) {
+            Some(version) => (
+                version as u32,
+                document.get(SETTINGS_KEY).cloned().unwrap_or(Value::Null),
+            ),
+            None => (1, document),
+        };
+
+        if version > self.version {
+            return Err(format!(
+                "settings `{}` have version {}, newer than supported version {}",
+                path, version, self.version
+            )
+            .into());
+        }
+
+        let migrated = version < self.version;
+        for (target, migration) in self.migrations.iter() {
+            if *target > version && *target <= self.version {
+                value = migration(value)?;
+                version = *target;
+            }
+        }
+
+        let value =
+            serde_json::from_value(value).map_err(|err| format!("settings `{}`: {}", path, err))?;
+        Ok((value, migrated))
+    }
+}
+
+type Subscriber<T> = Rc<RefCell<dyn FnMut(&T) -> Result<()>>>;
+
+type Subscribers<T> = Mutex<Vec<(u64, Subscriber<T>)>>;
+
+/// Handle of a [`Settings::subscribe()`] subscriber.
+/// The subscriber is removed by [`unsubscribe()`](Self::unsubscribe)
+/// or when the handle is dropped.
+#[must_use = "the subscriber is removed when the subscription is dropped"]
+pub struct SettingsSubscription<T> {
+    id: u64,
+    subscribers: Weak<Subscribers<T>>,
+}
+
+impl<T> SettingsSubscription<T> {
+    /// Stop receiving changes
+    pub fn unsubscribe(self) -> Result<()> {
+        self.remove()
+    }
+
+    fn remove(&self) -> Result<()> {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.lock()?.retain(|(id, _)| *id != self.id);
+        }
+        Ok(())
+    }
+}
+
+impl<T> Drop for SettingsSubscription<T> {
+    fn drop(&mut self) {
+        let _ = self.remove();
+    }
+}
+
+/// User settings of type `T` persisted in a file.
+///
+/// For usage example please refer to [Examples](self)
+pub struct Settings<T> {
+    name: String,
+    path: String,
+    format: SettingsFormat,
+    version: u32,
+    value: Arc<Mutex<T>>,
+    subscribers: Arc<Subscribers<T>>,
+    next_id: Arc<Mutex<u64>>,
+}
+
+impl<T> Clone for Settings<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            format: self.format,
+            version: self.version,
+            value: self.value.clone(),
+            subscribers: self.subscribers.clone(),
+            next_id: self.next_id.clone(),
+        }
+    }
+}
+
+impl<T> Settings<T>
+where
+    T: Serialize + DeserializeOwned + Default + Clone + 'static,
+{
+    /// Settings name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path of the settings file
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get a copy of the current settings
+    pub fn get(&self) -> Result<T> {
+        Ok(self.value.lock()?.clone())
+    }
+
+    /// Replace the settings, save them and notify subscribers
+    pub fn set(&self, value: T) -> Result<()> {
+        *self.value.lock()? = value;
+        self.save()?;
+        self.notify()
+    }
+
+    /// Modify the settings, save them and notify subscribers
+    pub fn update<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut value = self.get()?;
+        f(&mut value);
+        self.set(value)
+    }
+
+    /// Write the settings file
+    pub fn save(&self) -> Result<()> {
+        let value = serde_json::to_value(&*self.value.lock()?)
+            .map_err(|err| format!("settings `{}`: {}", self.name, err))?;
+        let mut document = serde_json::Map::new();
+        document.insert(VERSION_KEY.to_string(), Value::from(self.version));
+        document.insert(SETTINGS_KEY.to_string(), value);
+        let text = self.format.serialize(&Value::Object(document))?;
+        fs::write_atomic(&self.path, &text)
+    }
+
+    /// Call `callback` with the new settings whenever they change,
+    /// until the returned [`SettingsSubscription`] is dropped.
+    pub fn subscribe<F>(&self, callback: F) -> Result<SettingsSubscription<T>>
+    where
+        F: FnMut(&T) -> Result<()> + 'static,
+    {
+        Ok(SettingsSubscription {
+            id: self.add_subscriber(callback)?,
+            subscribers: Arc::downgrade(&self.subscribers),
+        })
+    }
+
+    /// Add a subscriber that stays for the lifetime of the settings
+    fn add_subscriber<F>(&self, callback: F) -> Result<u64>
+    where
+        F: FnMut(&T) -> Result<()> + 'static,
+    {
+        let mut next_id = self.next_id.lock()?;
+        let id = *next_id;
+        *next_id += 1;
+        self.subscribers
+            .lock()?
+            .push((id, Rc::new(RefCell::new(callback))));
+        Ok(id)
+    }
+
+    /// Subscribers are called without holding any lock, so they may
+    /// subscribe, unsubscribe or update the settings. A subscriber that
+    /// updates the settings is not called again for its own update.
+    fn notify(&self) -> Result<()> {
+        let value = self.get()?;
+        let subscribers = self.subscribers.lock()?.clone();
+        for (id, subscriber) in subscribers {
+            // skip subscribers removed by an earlier one
+            if !self.subscribers.lock()?.iter().any(|(sid, _)| *sid == id) {
+                continue;
+            }
+            let mut subscriber = match subscriber.try_borrow_mut() {
+                Ok(subscriber) => subscriber,
+                Err(_) => continue,
+            };
+            if let Err(err) = (*subscriber)(&value) {
+                log_error!("settings `{}` subscriber error: {:?}", self.name, err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Create a checkbox [`MenuItem`] bound to a boolean setting: the item
+    /// shows the value returned by `get` and clicking it stores the new
+    /// state with `set`. The item follows changes made elsewhere.
+    pub fn checkbox_menu_item<G, S>(&self, label: &str, get: G, set: S) -> Result<MenuItem>
+    where
+        G: Fn(&T) -> bool + 'static,
+        S: Fn(&mut T, bool) + 'static,
+    {
+        let get = Arc::new(get);
+        let settings = self.clone();
+        let get_ = get.clone();
+        let item = MenuItemBuilder::new()
+            .set_type(MenuItemType::Checkbox)
+            .label(label)
+            .checked(get(&*self.value.lock()?))
+            .callback(move |_| -> std::result::Result<(), JsValue> {
+                let checked = !get_(&settings.get()?);
+                settings.update(|value| set(value, checked))?;
+                Ok(())
+            })
+            .build()?;
+
+        let item_ = item.clone();
+        self.add_subscriber(move |value| {
+            js_sys::Reflect::set(&item_, &"checked".into(), &JsValue::from(get(value)))?;
+            Ok(())
+        })?;
+
+        Ok(item)
+    }
+}
+
+impl Application {
+    /// Make `settings` available through [`settings()`](Self::settings)
+    /// in this window, replacing settings registered under the same name.
+    pub fn register_settings<T>(&self, settings: &Settings<T>) -> Result<()>
+    where
+        T: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        let key = settings_key::<T>(settings.name());
+        let settings: Box<dyn Any> = Box::new(settings.clone());
+        self.settings.lock()?.insert(key, settings);
+        Ok(())
+    }
+
+    /// Get the settings registered under `name`. If none are registered,
+    /// they are loaded with [`SettingsBuilder`] defaults and registered.
+    pub fn settings<T>(&self, name: &str) -> Result<Settings<T>>
+    where
+        T: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        if let Some(settings) = self
+            .settings
+            .lock()?
+            .get(&settings_key::<T>(name))
+            .and_then(|settings| settings.downcast_ref::<Settings<T>>())
+        {
+            return Ok(settings.clone());
+        }
+
+        let settings = SettingsBuilder::<T>::new(name).build()?;
+        self.register_settings(&settings)?;
+        Ok(settings)
+    }
+}
+
+fn settings_key<T: 'static>(name: &str) -> String {
+    format!("{}:{}", name, std::any::type_name::<T>())
+}
+
+/// Settings registered with [`Application::register_settings()`], keyed by
+/// name and type
+pub type SettingsRegistry = Arc<Mutex<HashMap<String, Box<dyn Any>>>>;
@@ -13,7 +13,8 @@
 //!     })
 //!     .build()?;
 //!     
-//! nw_sys::app::register_global_hot_key(&shortcut);
+//! // unregistered automatically by `Application::quit()`
+//! app.register_global_shortcut(&shortcut)?;
 //! ```
 //!
 
@@ -0,0 +1,165 @@
+//!
+//! Reactive application state shared by all windows.
+//!
+//! [`AppState`] keeps a serde-serialized value in the Node.js `global`
+//! object, so every window reads and updates the same state. Changes
+//! are broadcast to subscribers in all windows over a [`Channel`].
+//!
+//! # Synopsis
+//! ```rust
+//! #[derive(Default, Clone, Serialize, Deserialize)]
+//! struct Session {
+//!     user: Option<String>,
+//!     documents: Vec<String>,
+//! }
+//!
+//! // attach to the state from any window
+//! let session = app().unwrap().state::<Session>("session")?;
+//!
+//! // react to changes made in any window
+//! let subscription = session.subscribe(|session| {
+//!     log_info!("documents: {:?}", session.documents);
+//!     Ok(())
+//! })?;
+//!
+//! // update the state
+//! session.update(|session| {
+//!     session.documents.push("notes.txt".into());
+//! })?;
+//!
+//! // persist every change (only needs to be done in one window)
+//! session.persist_with(|session| save_session(session))?;
+//! ```
+//!
+
+use crate::channel::{Channel, Subscription};
+use crate::global::get_or_create_object;
+use js_sys::Reflect;
+use nw_sys::result::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use wasm_bindgen::prelude::*;
+
+const REGISTRY: &str = "__workflow_nw_state";
+
+/// State of type `T` shared by all application windows under a name.
+///
+/// For usage example please refer to [Examples](self)
+pub struct AppState<T> {
+    name: String,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for AppState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> AppState<T>
+where
+    T: Serialize + DeserializeOwned + Default + 'static,
+{
+    /// Attach to the state `name`, initializing it with `T::default()`
+    /// if no window has created it yet.
+    pub fn new(name: &str) -> Result<Self> {
+        Self::with_init(name, || Ok(T::default()))
+    }
+}
+
+impl<T> AppState<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    /// Attach to the state `name`. If no window has created it yet,
+    /// it is initialized with the value returned by `init`,
+    /// e.g. a value loaded from disk.
+    pub fn with_init<F>(name: &str, init: F) -> Result<Self>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let state = Self {
+            name: name.to_string(),
+            _marker: PhantomData,
+        };
+
+        if state.load_json()?.is_none() {
+            state.store(&init()?)?;
+        }
+
+        Ok(state)
+    }
+
+    /// State name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn channel(&self) -> Channel<T> {
+        Channel::new(&format!("{}:{}", REGISTRY, self.name))
+    }
+
+    fn load_json(&self) -> Result<Option<String>> {
+        let registry = get_or_create_object(REGISTRY)?;
+        Ok(Reflect::get(&registry, &JsValue::from(self.name.as_str()))?.as_string())
+    }
+
+    fn store(&self, state: &T) -> Result<()> {
+        let text = serde_json::to_string(state)
+            .map_err(|err| format!("AppState `{}` serialize error: {}", self.name, err))?;
+        let registry = get_or_create_object(REGISTRY)?;
+        Reflect::set(
+            &registry,
+            &JsValue::from(self.name.as_str()),
+            &JsValue::from(text),
+        )?;
+        Ok(())
+    }
+
+    /// Get a copy of the current state
+    pub fn get(&self) -> Result<T> {
+        let text = self
+            .load_json()?
+            .ok_or_else(|| format!("AppState `{}` is not initialized", self.name))?;
+        let state = serde_json::from_str(&text)
+            .map_err(|err| format!("AppState `{}` deserialize error: {}", self.name, err))?;
+        Ok(state)
+    }
+
+    /// Replace the state and notify subscribers in all windows
+    pub fn set(&self, state: T) -> Result<()> {
+        self.store(&state)?;
+        self.channel().publish(&state)
+    }
+
+    /// Modify the state and notify subscribers in all windows
+    pub fn update<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut state = self.get()?;
+        f(&mut state);
+        self.set(state)
+    }
+
+    /// Receive the new state whenever it changes in any window.
+    /// The subscription is removed when this window closes.
+    pub fn subscribe<F>(&self, callback: F) -> Result<Subscription>
+    where
+        F: FnMut(T) -> Result<()> + 'static,
+    {
+        self.channel().subscribe(callback)
+    }
+
+    /// Call `persist` with the new state whenever it changes in any window.
+    /// Typically registered once, by the main window.
+    pub fn persist_with<F>(&self, mut persist: F) -> Result<Subscription>
+    where
+        F: FnMut(&T) -> Result<()> + 'static,
+    {
+        self.subscribe(move |state| persist(&state))
+    }
+}
@@ -4,7 +4,7 @@
 //! # Synopsis
 //! ```rust
 //! // create Tray icon menu without submenus
-//! TrayMenuBuilder::new()
+//! let tray = TrayMenuBuilder::new()
 //!     .icon("resources/icons/tray-icon@2x.png")
 //!     .icons_are_templates(false)
 //!     .callback(|_|{
@@ -13,6 +13,18 @@
 //!     })
 //!     .build()?;
 //!
+//! // update it at runtime
+//! tray.set_icon("resources/icons/tray-icon-busy@2x.png")?;
+//! tray.set_tooltip("Syncing...")?;
+//! tray.replace_click_callback(|_|{
+//!     window().alert_with_message("Sync in progress")?;
+//!     Ok(())
+//! })?;
+//!
+//! // remove the tray and release its click callback;
+//! // dropping the last handle has the same effect
+//! tray.remove()?;
+//!
 //! // create Tray menu icon with submenus
 //! let submenu_1 = MenuItemBuilder::new()
 //!     .label("Say hi")
@@ -26,26 +38,265 @@
 //! let exit_menu = MenuItemBuilder::new()
 //!     .label("Exit")
 //!     .callback(move |_|->std::result::Result<(), JsValue>{
-//!         nw_sys::app::close_all_windows();
+//!         // run close handlers and shutdown hooks, remove the tray and quit
+//!         spawn_local(async move {
+//!             if let Err(err) = app().unwrap().quit().await {
+//!                 log_error!("quit error: {:?}", err);
+//!             }
+//!         });
 //!         Ok(())
 //!     }).build()?;
 //!     
+//! // keep the handle for as long as the tray should be shown
 //! let _tray = TrayMenuBuilder::new()
 //!     .icon("resources/icons/tray-icon@2x.png")
 //!     .icons_are_templates(false)
 //!     .submenus(vec![submenu_1, menu_separator(), exit_menu])
 //!     .build()?;
 //!
+//! // or keep the tray until `Application::quit()`
+//! TrayMenuBuilder::new()
+//!     .icon("resources/icons/tray-icon@2x.png")
+//!     .build()?
+//!     .detach()?;
+//!
 //! ```
 //!
 
 use crate::application::app;
+use crate::manifest::{manifest, Manifest};
+use js_sys::Function;
 use nw_sys::{menu_item::MenuItem, tray::Options, Menu, Tray};
 use nw_sys::{prelude::*, result::Result};
+use std::sync::{Arc, Mutex, Weak};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use web_sys::MouseEvent;
 use workflow_wasm::prelude::*;
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Tray)]
+    type TrayExt;
+
+    #[wasm_bindgen(method, js_name = removeListener)]
+    fn remove_listener(this: &TrayExt, event: &str, callback: &Function);
+}
+
+type ClickCallback = Callback<CallbackClosure<MouseEvent>>;
+
+struct TrayInner {
+    tray: Tray,
+    click: Option<ClickCallback>,
+    removed: bool,
+}
+
+impl TrayInner {
+    fn remove(&mut self) {
+        if !self.removed {
+            self.removed = true;
+            self.tray.remove();
+            release(self.click.take());
+        }
+    }
+}
+
+impl Drop for TrayInner {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+/// Drop a click callback after the current JS task, as it may be
+/// the callback that is running, e.g. when a tray removes itself on click
+fn release(callback: Option<ClickCallback>) {
+    if let Some(callback) = callback {
+        wasm_bindgen_futures::spawn_local(async move {
+            drop(callback);
+        });
+    }
+}
+
+/// Tray created by [`TrayMenuBuilder::build()`].
+///
+/// Clones refer to the same tray. The tray is removed and its click
+/// callback released by [`remove()`](Self::remove) or when the last
+/// handle is dropped.
+///
+/// Dropping the handle returned by [`TrayMenuBuilder::build()`] right away
+/// removes the tray immediately. Code that used to call `build()` and
+/// discard the result must keep the handle or call [`detach()`](Self::detach).
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+#[must_use = "the tray is removed when the last handle is dropped, use `detach()` to keep it"]
+pub struct TrayHandle {
+    inner: Arc<Mutex<TrayInner>>,
+}
+
+/// Weak reference to a [`TrayHandle`] that does not keep the tray alive
+#[derive(Clone)]
+pub struct WeakTrayHandle {
+    inner: Weak<Mutex<TrayInner>>,
+}
+
+impl WeakTrayHandle {
+    pub fn upgrade(&self) -> Option<TrayHandle> {
+        self.inner.upgrade().map(|inner| TrayHandle { inner })
+    }
+}
+
+impl TrayHandle {
+    fn new(tray: Tray, click: Option<ClickCallback>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TrayInner {
+                tray,
+                click,
+                removed: false,
+            })),
+        }
+    }
+
+    pub fn downgrade(&self) -> WeakTrayHandle {
+        WeakTrayHandle {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// The underlying `nw.Tray` object
+    pub fn tray(&self) -> Result<Tray> {
+        Ok(self.inner.lock()?.tray.clone())
+    }
+
+    pub fn is_removed(&self) -> Result<bool> {
+        Ok(self.inner.lock()?.removed)
+    }
+
+    fn with_tray<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&Tray),
+    {
+        let inner = self.inner.lock()?;
+        if inner.removed {
+            return Err("tray has been removed".to_string().into());
+        }
+        f(&inner.tray);
+        Ok(())
+    }
+
+    /// Set the title of the tray.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#traytitle)
+    pub fn set_title(&self, title: &str) -> Result<()> {
+        self.with_tray(|tray| tray.set_title(title))
+    }
+
+    /// Set the icon of the tray.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#trayicon)
+    pub fn set_icon(&self, icon: &str) -> Result<()> {
+        self.with_tray(|tray| tray.set_icon(icon))
+    }
+
+    /// (Mac) Set the alternate (active) tray icon.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#trayalticon-mac)
+    pub fn set_alticon(&self, alticon: &str) -> Result<()> {
+        self.with_tray(|tray| tray.set_alticon(alticon))
+    }
+
+    /// Set the tooltip of the tray.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#traytooltip)
+    pub fn set_tooltip(&self, tooltip: &str) -> Result<()> {
+        self.with_tray(|tray| tray.set_tooltip(tooltip))
+    }
+
+    /// Replace the menu of the tray.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#traymenu)
+    pub fn set_menu(&self, menu: &Menu) -> Result<()> {
+        self.with_tray(|tray| tray.set_menu(menu))
+    }
+
+    /// Replace the menu of the tray with the given items.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#traymenu)
+    pub fn set_submenus(&self, items: Vec<MenuItem>) -> Result<()> {
+        let menu = nw_sys::Menu::new();
+        for menu_item in items {
+            menu.append(&menu_item);
+        }
+        self.set_menu(&menu)
+    }
+
+    /// Replace the callback function called when the tray icon is clicked,
+    /// releasing the previous one.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#event-click)
+    pub fn replace_click_callback<F>(&self, callback: F) -> Result<()>
+    where
+        F: FnMut(MouseEvent) -> std::result::Result<(), JsValue> + 'static,
+    {
+        let mut inner = self.inner.lock()?;
+        if inner.removed {
+            return Err("tray has been removed".to_string().into());
+        }
+        let callback = Callback::new(callback);
+        inner.tray.on("click", callback.as_ref());
+        let previous = inner.click.replace(callback);
+        if let Some(previous) = previous.as_ref() {
+            inner
+                .tray
+                .unchecked_ref::<TrayExt>()
+                .remove_listener("click", previous.as_ref());
+        }
+        release(previous);
+        Ok(())
+    }
+
+    /// Remove the click callback function
+    pub fn remove_click_callback(&self) -> Result<()> {
+        let mut inner = self.inner.lock()?;
+        let previous = inner.click.take();
+        if let Some(previous) = previous.as_ref() {
+            inner
+                .tray
+                .unchecked_ref::<TrayExt>()
+                .remove_listener("click", previous.as_ref());
+        }
+        release(previous);
+        Ok(())
+    }
+
+    /// Remove the tray and release its click callback.
+    /// Does nothing if the tray is already removed.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#trayremove)
+    pub fn remove(&self) -> Result<()> {
+        self.inner.lock()?.remove();
+        if let Some(app) = app() {
+            app.detached_trays
+                .lock()?
+                .retain(|tray| !Arc::ptr_eq(&tray.inner, &self.inner));
+        }
+        Ok(())
+    }
+
+    /// Keep the tray without holding a handle. It stays until
+    /// [`remove()`](Self::remove) is called on another handle or
+    /// [`Application::quit()`](crate::application::Application::quit)
+    /// removes all trays.
+    pub fn detach(self) -> Result<()> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+        app.detached_trays.lock()?.push(self);
+        Ok(())
+    }
+}
+
 /// Provides a builder pattern for constructing a system tray menu
 /// for the application.
 ///
@@ -105,6 +356,18 @@ impl TrayMenuBuilder {
         self.set("icon", JsValue::from(icon))
     }
 
+    /// Set the icon from the `window` section of the application manifest
+    /// and the tooltip to the window title or application `name`.
+    /// Setters called afterwards take precedence.
+    pub fn manifest_defaults(mut self) -> Result<Self> {
+        let manifest = manifest::<Manifest>()?;
+        self = self.tooltip(&manifest.window.title.unwrap_or(manifest.name));
+        if let Some(icon) = manifest.window.icon {
+            self = self.icon(&icon);
+        }
+        Ok(self)
+    }
+
     /// (Mac) Set the alternate (active) tray icon.
     ///
     /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Tray/#trayalticon-mac)
@@ -184,18 +447,22 @@ impl TrayMenuBuilder {
         }
     }
 
-    pub fn build(self) -> Result<Tray> {
+    /// Create the tray. It is removed when the last [`TrayHandle`] is dropped,
+    /// by [`TrayHandle::remove()`] or by [`Application::quit()`](crate::application::Application::quit).
+    ///
+    /// The click callback is owned by the handle, so the [`Application`](crate::application::Application)
+    /// is only needed to remove the tray on quit; without it the tray is
+    /// created but not tracked.
+    pub fn build(self) -> Result<TrayHandle> {
         let (tray, callback) = self.build_impl()?;
-
-        if let Some(callback) = callback {
-            let app = match app() {
-                Some(app) => app,
-                None => return Err("app is not initialized".to_string().into()),
-            };
-            app.callbacks.retain(callback)?;
+        let handle = TrayHandle::new(tray, callback);
+        if let Some(app) = app() {
+            let mut trays = app.trays.lock()?;
+            trays.retain(|tray| tray.upgrade().is_some());
+            trays.push(handle.downgrade());
         }
 
-        Ok(tray)
+        Ok(handle)
     }
 
     pub fn finalize(self) -> Result<(Tray, Option<Callback<CallbackClosure<MouseEvent>>>)> {
@@ -0,0 +1,277 @@
+//!
+//! Tray icon and tooltip driven by application states, with optional
+//! animated icons.
+//!
+//! # Synopsis
+//! ```rust
+//! #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+//! enum Connection { Idle, Syncing, Error, Offline }
+//!
+//! let tray = TrayMenuBuilder::new()
+//!     .icon("resources/icons/idle.png")
+//!     .build()?;
+//!
+//! let status = TrayStatusBuilder::new(&tray)
+//!     .state(Connection::Idle, TrayState::icon("resources/icons/idle.png").tooltip("Connected"))
+//!     .state(
+//!         Connection::Syncing,
+//!         TrayState::animated(
+//!             &["resources/icons/sync-1.png", "resources/icons/sync-2.png", "resources/icons/sync-3.png"],
+//!             150,
+//!         )
+//!         .tooltip("Syncing..."),
+//!     )
+//!     .state(Connection::Error, TrayState::icon("resources/icons/error.png").tooltip("Sync failed"))
+//!     .state(Connection::Offline, TrayState::icon("resources/icons/offline.png").tooltip("Offline"))
+//!     .build(Connection::Idle)?;
+//!
+//! // the animation of the previous state is stopped on every switch
+//! // and on `Application::quit()`
+//! status.set(Connection::Syncing)?;
+//! status.set(Connection::Idle)?;
+//! ```
+//!
+
+use crate::application::{app, ShutdownHookId};
+use crate::global::{clear_interval, set_interval};
+use crate::tray::TrayHandle;
+use nw_sys::result::Result;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
+
+/// Appearance of the tray in one state
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayState {
+    /// Icon frames, a single frame for a static icon
+    pub frames: Vec<String>,
+    /// Time between frames in milliseconds
+    pub interval: u32,
+    pub tooltip: Option<String>,
+    pub title: Option<String>,
+}
+
+impl TrayState {
+    /// A static icon
+    pub fn icon(icon: &str) -> Self {
+        Self {
+            frames: vec![icon.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// An icon cycling through `frames` every `interval` milliseconds
+    pub fn animated(frames: &[&str], interval: u32) -> Self {
+        Self {
+            frames: frames.iter().map(|frame| frame.to_string()).collect(),
+            interval,
+            ..Default::default()
+        }
+    }
+
+    /// Tooltip shown in this state
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    /// Title shown next to the icon in this state
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    fn is_animated(&self) -> bool {
+        self.frames.len() > 1 && self.interval > 0
+    }
+}
+
+struct Animation {
+    interval_id: JsValue,
+    callback_id: CallbackId,
+}
+
+impl Animation {
+    fn stop(self) {
+        clear_interval(&self.interval_id);
+        if let Some(app) = app() {
+            let _ = app.callbacks.remove(&self.callback_id);
+        }
+    }
+}
+
+struct Inner<S> {
+    tray: TrayHandle,
+    states: HashMap<S, TrayState>,
+    current: Option<S>,
+    animation: Option<Animation>,
+    shutdown_hook: Option<ShutdownHookId>,
+}
+
+impl<S> Inner<S> {
+    fn stop_animation(&mut self) {
+        if let Some(animation) = self.animation.take() {
+            animation.stop();
+        }
+    }
+}
+
+impl<S> Drop for Inner<S> {
+    fn drop(&mut self) {
+        self.stop_animation();
+        if let (Some(id), Some(app)) = (self.shutdown_hook.take(), app()) {
+            let _ = app.remove_shutdown_hook(id);
+        }
+    }
+}
+
+/// Builder for a [`TrayStatus`]
+///
+/// For usage example please refer to [Examples](self)
+pub struct TrayStatusBuilder<S> {
+    pub tray: TrayHandle,
+    pub states: HashMap<S, TrayState>,
+}
+
+impl<S> TrayStatusBuilder<S>
+where
+    S: Clone + Eq + Hash + 'static,
+{
+    pub fn new(tray: &TrayHandle) -> Self {
+        Self {
+            tray: tray.clone(),
+            states: HashMap::new(),
+        }
+    }
+
+    /// Appearance of the tray in `state`
+    pub fn state(mut self, state: S, appearance: TrayState) -> Self {
+        self.states.insert(state, appearance);
+        self
+    }
+
+    /// Create the controller and switch the tray to `initial`
+    pub fn build(self, initial: S) -> Result<TrayStatus<S>> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let status = TrayStatus {
+            inner: Arc::new(Mutex::new(Inner {
+                tray: self.tray,
+                states: self.states,
+                current: None,
+                animation: None,
+                shutdown_hook: None,
+            })),
+        };
+        status.set(initial)?;
+
+        let inner = Arc::downgrade(&status.inner);
+        let shutdown_hook = app.on_shutdown(move || {
+            let inner = inner.clone();
+            async move {
+                if let Some(inner) = inner.upgrade() {
+                    inner.lock()?.stop_animation();
+                }
+                Ok(())
+            }
+        })?;
+        status.inner.lock()?.shutdown_hook = Some(shutdown_hook);
+
+        Ok(status)
+    }
+}
+
+/// Switches the icon and tooltip of a tray between application states.
+/// Created by [`TrayStatusBuilder::build()`].
+///
+/// A running animation is stopped when the state changes, on
+/// [`stop()`](Self::stop), on [`Application::quit()`](crate::application::Application::quit)
+/// and when the last clone is dropped.
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+pub struct TrayStatus<S> {
+    inner: Arc<Mutex<Inner<S>>>,
+}
+
+impl<S> TrayStatus<S>
+where
+    S: Clone + Eq + Hash + 'static,
+{
+    /// The current state
+    pub fn current(&self) -> Result<Option<S>> {
+        Ok(self.inner.lock()?.current.clone())
+    }
+
+    /// Switch the tray to `state`. Does nothing if it is the current state.
+    pub fn set(&self, state: S) -> Result<()> {
+        let mut inner = self.inner.lock()?;
+        if inner.current.as_ref() == Some(&state) {
+            return Ok(());
+        }
+        let appearance = match inner.states.get(&state) {
+            Some(appearance) => appearance.clone(),
+            None => return Err("tray state is not registered".to_string().into()),
+        };
+
+        inner.stop_animation();
+        if let Some(icon) = appearance.frames.first() {
+            inner.tray.set_icon(icon)?;
+        }
+        if let Some(tooltip) = appearance.tooltip.as_ref() {
+            inner.tray.set_tooltip(tooltip)?;
+        }
+        if let Some(title) = appearance.title.as_ref() {
+            inner.tray.set_title(title)?;
+        }
+        if appearance.is_animated() {
+            inner.animation = Some(animate(
+                &inner.tray,
+                appearance.frames,
+                appearance.interval,
+            )?);
+        }
+        inner.current = Some(state);
+
+        Ok(())
+    }
+
+    /// Stop the animation of the current state, leaving the current frame
+    pub fn stop(&self) -> Result<()> {
+        self.inner.lock()?.stop_animation();
+        Ok(())
+    }
+}
+
+fn animate(tray: &TrayHandle, frames: Vec<String>, interval: u32) -> Result<Animation> {
+    let app = match app() {
+        Some(app) => app,
+        None => return Err("app is not initialized".to_string().into()),
+    };
+
+    let tray = tray.clone();
+    let mut frame = 0;
+    let callback = Callback::new(move |_: JsValue| -> std::result::Result<(), JsValue> {
+        // the tray may have been removed while the state is unchanged
+        if !tray.is_removed()? {
+            frame = (frame + 1) % frames.len();
+            tray.set_icon(&frames[frame])?;
+        }
+        Ok(())
+    });
+
+    let animation = Animation {
+        interval_id: set_interval(callback.as_ref(), interval),
+        callback_id: callback.get_id(),
+    };
+    app.callbacks.retain(callback)?;
+
+    Ok(animation)
+}
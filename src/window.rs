@@ -0,0 +1,1057 @@
+//!
+//! Builder for application windows and a typed [`WindowHandle`]
+//! wrapping [`nw_sys::Window`].
+//!
+//! # Synopsis
+//! ```rust
+//! // create a window and wait for it to be ready
+//! let win = WindowBuilder::new("/root/index.html")
+//!     .id("main")
+//!     .title("My App")
+//!     .size(800, 600)
+//!     .min_size(400, 300)
+//!     .position(0, 100)
+//!     .resizable(true)
+//!     .build()
+//!     .await?;
+//!
+//! win.set_title("My App - Untitled");
+//! win.focus();
+//!
+//! // open the settings window or focus it if it is already open
+//! let settings = app.focus_or_open(
+//!     "settings",
+//!     WindowBuilder::new("/root/settings.html").title("Settings")
+//! ).await?;
+//!
+//! // close it by id
+//! app.close("settings")?;
+//!
+//! // subscribe to window events
+//! win.on(WindowEvent::Resize, |event| {
+//!     if let WindowEventData::Resize { width, height } = event {
+//!         log_info!("window resized to {width}x{height}");
+//!     }
+//!     Ok(())
+//! })?;
+//! ```
+//!
+
+use crate::application::app;
+use crate::display::{
+    Anchor, DisplayChoice, DisplaySelector, DisplaySource, Placement, Rect, DEFAULT_WINDOW_SIZE,
+};
+use crate::fs;
+use crate::manifest::{manifest, Manifest};
+use futures::channel::oneshot;
+use nw_sys::{prelude::*, result::Result};
+use std::future::Future;
+use std::pin::Pin;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// `nw.Window` methods not bound by [`nw_sys::Window`]
+    #[wasm_bindgen(extends = nw_sys::Window)]
+    pub(crate) type WindowExt;
+
+    #[wasm_bindgen(method, js_name = removeListener)]
+    fn remove_listener(this: &WindowExt, event_name: &str, callback: &js_sys::Function);
+
+    #[wasm_bindgen(method, js_name = capturePage)]
+    fn capture_page_with_config(
+        this: &WindowExt,
+        callback: &js_sys::Function,
+        config: &js_sys::Object,
+    );
+
+    #[wasm_bindgen(method, js_name = captureScreenshot)]
+    fn capture_screenshot(this: &WindowExt, options: &js_sys::Object, callback: &js_sys::Function);
+
+    #[wasm_bindgen(method, js_name = setProgressBar)]
+    fn set_progress_bar(this: &WindowExt, progress: f64);
+
+    #[wasm_bindgen(method, js_name = setBadgeLabel)]
+    fn set_badge_label(this: &WindowExt, label: &str);
+
+    #[wasm_bindgen(method, js_name = requestAttention)]
+    fn request_attention(this: &WindowExt, attention: &JsValue);
+
+    #[wasm_bindgen(js_namespace = Buffer, js_name = from)]
+    fn buffer_from(data: &str, encoding: &str) -> js_sys::Uint8Array;
+}
+
+/// Window events that can be subscribed to via [`WindowHandle::on()`]
+///
+/// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#event-close)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowEvent {
+    Close,
+    Closed,
+    Loading,
+    Loaded,
+    Focus,
+    Blur,
+    Minimize,
+    Restore,
+    Maximize,
+    Move,
+    Resize,
+    EnterFullscreen,
+    LeaveFullscreen,
+    Zoom,
+}
+
+impl WindowEvent {
+    /// NW event name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Close => "close",
+            Self::Closed => "closed",
+            Self::Loading => "loading",
+            Self::Loaded => "loaded",
+            Self::Focus => "focus",
+            Self::Blur => "blur",
+            Self::Minimize => "minimize",
+            Self::Restore => "restore",
+            Self::Maximize => "maximize",
+            Self::Move => "move",
+            Self::Resize => "resize",
+            Self::EnterFullscreen => "enter-fullscreen",
+            Self::LeaveFullscreen => "leave-fullscreen",
+            Self::Zoom => "zoom",
+        }
+    }
+
+    fn data(&self, window: &nw_sys::Window, value: JsValue) -> WindowEventData {
+        match self {
+            Self::Close => WindowEventData::Close {
+                quit: value.as_string().map(|v| v == "quit").unwrap_or(false),
+            },
+            Self::Closed => WindowEventData::Closed,
+            Self::Loading => WindowEventData::Loading,
+            Self::Loaded => WindowEventData::Loaded,
+            Self::Focus => WindowEventData::Focus,
+            Self::Blur => WindowEventData::Blur,
+            Self::Minimize => WindowEventData::Minimize,
+            Self::Restore => WindowEventData::Restore,
+            Self::Maximize => WindowEventData::Maximize,
+            Self::Move => WindowEventData::Move {
+                x: window.x(),
+                y: window.y(),
+            },
+            Self::Resize => WindowEventData::Resize {
+                width: window.width(),
+                height: window.height(),
+            },
+            Self::EnterFullscreen => WindowEventData::EnterFullscreen,
+            Self::LeaveFullscreen => WindowEventData::LeaveFullscreen,
+            Self::Zoom => WindowEventData::Zoom {
+                level: value.as_f64().unwrap_or(0.0),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for WindowEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Payload passed to [`WindowHandle::on()`] callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowEventData {
+    /// The window is requested to close. `quit` is `true` when
+    /// the close is triggered by quitting the application (Mac `Cmd+Q`).
+    Close {
+        quit: bool,
+    },
+    Closed,
+    Loading,
+    Loaded,
+    Focus,
+    Blur,
+    Minimize,
+    Restore,
+    Maximize,
+    Move {
+        x: i32,
+        y: i32,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    EnterFullscreen,
+    LeaveFullscreen,
+    Zoom {
+        level: f64,
+    },
+}
+
+/// Decision returned by a close request handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseDecision {
+    /// Let the window close
+    Allow,
+    /// Keep the window open
+    Veto,
+}
+
+/// Information about a close request passed to the handler
+/// registered with [`WindowHandle::on_close_requested()`]
+#[derive(Clone)]
+pub struct CloseContext {
+    /// The window being closed
+    pub window: WindowHandle,
+    /// `true` if the close is part of quitting the application
+    pub quit: bool,
+}
+
+/// Async close request handler
+pub type CloseHandler = Arc<dyn Fn(CloseContext) -> Pin<Box<dyn Future<Output = CloseDecision>>>>;
+
+/// Image format of [`WindowHandle::capture_page()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    Png,
+    Jpeg,
+}
+
+impl CaptureFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+        }
+    }
+}
+
+/// Provides a builder pattern for creating application windows.
+///
+/// For usage example please refer to [Examples](self)
+pub struct WindowBuilder {
+    pub url: String,
+    pub id: Option<String>,
+    pub options: nw_sys::window::Options,
+    pub placement: Option<Placement>,
+}
+
+impl WindowBuilder {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            id: None,
+            options: nw_sys::window::Options::new(),
+            placement: None,
+        }
+    }
+
+    pub fn set(mut self, key: &str, value: JsValue) -> Self {
+        self.options = self.options.set(key, value);
+        self
+    }
+
+    /// The id used to identify the window. This will be used to remember
+    /// the size and position of the window and restore that geometry
+    /// when a window with the same id is opened.
+    ///
+    /// The window is also tracked by the [`Application`](crate::application::Application)
+    /// under this id, see [`Application::window()`](crate::application::Application::window).
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#id)
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self.set("id", JsValue::from(id))
+    }
+
+    /// The default title of window created by NW.js.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#title)
+    pub fn title(self, title: &str) -> Self {
+        self.set("title", JsValue::from(title))
+    }
+
+    /// The initial inner width and height of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#width)
+    pub fn size(self, width: u32, height: u32) -> Self {
+        self.set("width", JsValue::from(width))
+            .set("height", JsValue::from(height))
+    }
+
+    /// The minimum inner width and height of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#min_width)
+    pub fn min_size(self, width: u32, height: u32) -> Self {
+        self.set("min_width", JsValue::from(width))
+            .set("min_height", JsValue::from(height))
+    }
+
+    /// The maximum inner width and height of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#max_width)
+    pub fn max_size(self, width: u32, height: u32) -> Self {
+        self.set("max_width", JsValue::from(width))
+            .set("max_height", JsValue::from(height))
+    }
+
+    /// The left and top position of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#windowopenurl-options-callback)
+    pub fn position(self, x: i32, y: i32) -> Self {
+        self.set("x", JsValue::from(x)).set("y", JsValue::from(y))
+    }
+
+    /// Place the window on the selected display, keeping the anchor set with
+    /// [`position_anchored()`](Self::position_anchored) (centered by default).
+    /// If the display is not connected, the primary display is used.
+    /// Overrides [`position()`](Self::position); a position restored from
+    /// a saved window state takes precedence.
+    pub fn on_display(mut self, display: DisplaySelector) -> Self {
+        let mut placement = self.placement.take().unwrap_or_default();
+        placement.display = DisplayChoice::Select(display);
+        self.placement = Some(placement);
+        self
+    }
+
+    /// Center the window on the selected display, same as
+    /// [`on_display()`](Self::on_display) followed by
+    /// `position_anchored(Anchor::Center, 0, 0)`.
+    ///
+    /// `center_on()` and [`position_anchored()`](Self::position_anchored)
+    /// both set the anchor, the one called last wins. Use
+    /// [`on_display()`](Self::on_display) to choose the display of an
+    /// anchored window.
+    pub fn center_on(self, display: DisplaySelector) -> Self {
+        self.on_display(display)
+            .position_anchored(Anchor::Center, 0, 0)
+    }
+
+    /// Place the window on the display under the cursor or on the display
+    /// showing another window, e.g. the parent of a dialog.
+    /// The window is centered unless [`position_anchored()`](Self::position_anchored)
+    /// is used.
+    pub fn on_display_of(mut self, source: DisplaySource) -> Self {
+        let mut placement = self.placement.take().unwrap_or_default();
+        placement.display = DisplayChoice::Of(source);
+        self.placement = Some(placement);
+        self
+    }
+
+    /// Align the window to an `anchor` of the display work area, moved by
+    /// `dx`/`dy` pixels. The window is kept within the work area.
+    /// The display is chosen with [`on_display()`](Self::on_display) or
+    /// [`on_display_of()`](Self::on_display_of), default is the primary display.
+    /// Replaces the anchor of an earlier [`center_on()`](Self::center_on).
+    pub fn position_anchored(mut self, anchor: Anchor, dx: i32, dy: i32) -> Self {
+        let mut placement = self.placement.take().unwrap_or_default();
+        placement.anchor = anchor;
+        placement.offset = (dx, dy);
+        self.placement = Some(placement);
+        self
+    }
+
+    fn resolve_placement(&self) -> Result<nw_sys::window::Options> {
+        let placement = match self.placement.as_ref() {
+            Some(placement) => placement,
+            None => return Ok(self.options.clone()),
+        };
+
+        let dimension = |key: &str| -> Result<Option<u32>> {
+            let value = js_sys::Reflect::get(&self.options, &JsValue::from(key))?;
+            Ok(value.as_f64().map(|value| value as u32))
+        };
+        let mut options: nw_sys::window::Options =
+            js_sys::Object::assign(&js_sys::Object::new(), &self.options).unchecked_into();
+
+        // the window must open with the size used for placement, so a
+        // fallback size is also passed to NW
+        let window = manifest::<Manifest>()
+            .map(|manifest| manifest.window)
+            .unwrap_or_default();
+        let width = match dimension("width")? {
+            Some(width) => width,
+            None => {
+                let width = window.width.unwrap_or(DEFAULT_WINDOW_SIZE.0);
+                options = options.set("width", JsValue::from(width));
+                width
+            }
+        };
+        let height = match dimension("height")? {
+            Some(height) => height,
+            None => {
+                let height = window.height.unwrap_or(DEFAULT_WINDOW_SIZE.1);
+                options = options.set("height", JsValue::from(height));
+                height
+            }
+        };
+        let size = (width, height);
+        match placement.resolve(size)? {
+            Some((x, y)) => Ok(options
+                .set("x", JsValue::from(x))
+                .set("y", JsValue::from(y))),
+            None => Ok(options),
+        }
+    }
+
+    /// Specify whether the window has a frame. Default is `true`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#frame)
+    pub fn frame(self, frame: bool) -> Self {
+        self.set("frame", JsValue::from(frame))
+    }
+
+    /// Whether to turn on transparent window mode.
+    /// The default value is `false`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#transparent)
+    pub fn transparent(self, transparent: bool) -> Self {
+        self.set("transparent", JsValue::from(transparent))
+    }
+
+    /// Whether the window should always stay on top of other windows.
+    /// Default is `false`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#always_on_top)
+    pub fn always_on_top(self, always_on_top: bool) -> Self {
+        self.set("always_on_top", JsValue::from(always_on_top))
+    }
+
+    /// Whether window is resizable. Default is `true`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#resizable)
+    pub fn resizable(self, resizable: bool) -> Self {
+        self.set("resizable", JsValue::from(resizable))
+    }
+
+    /// Specify whether the window is shown on startup. Default is `true`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#show)
+    pub fn show(self, show: bool) -> Self {
+        self.set("show", JsValue::from(show))
+    }
+
+    /// Path to window’s icon
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#icon)
+    pub fn icon(self, icon: &str) -> Self {
+        self.set("icon", JsValue::from(icon))
+    }
+
+    /// Whether to use Kiosk mode. In Kiosk mode, the app will be fullscreen
+    /// and try to prevent users from leaving the app.
+    /// Default is `false`.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Manifest%20Format/#kiosk)
+    pub fn kiosk(self, kiosk: bool) -> Self {
+        self.set("kiosk", JsValue::from(kiosk))
+    }
+
+    /// Set the title and icon from the `window` section of the application
+    /// manifest, using the application `name` if no title is specified.
+    /// Setters called afterwards take precedence.
+    pub fn manifest_defaults(mut self) -> Result<Self> {
+        let manifest = manifest::<Manifest>()?;
+        self = self.title(&manifest.window.title.unwrap_or(manifest.name));
+        if let Some(icon) = manifest.window.icon {
+            self = self.icon(&icon);
+        }
+        Ok(self)
+    }
+
+    /// Open the window and resolve to a [`WindowHandle`] once NW
+    /// has created it.
+    pub async fn build(self) -> Result<WindowHandle> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let (sender, receiver) = oneshot::channel::<nw_sys::Window>();
+        let mut sender = Some(sender);
+
+        let mut callback = Callback::default();
+        let app_clone = app.clone();
+        let callback_id = callback.get_id();
+        callback.set_closure(move |win: JsValue| {
+            let _ = app_clone.callbacks.remove(&callback_id);
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(win.unchecked_into::<nw_sys::Window>());
+            }
+        });
+
+        let options = self.resolve_placement()?;
+        let options = match self.id.as_ref() {
+            Some(id) => app.apply_window_state(id, &options)?,
+            None => options,
+        };
+
+        nw_sys::window::open_with_options_and_callback(&self.url, &options, callback.as_ref());
+        app.callbacks.retain(callback)?;
+
+        let window = receiver
+            .await
+            .map_err(|_| "WindowBuilder::build(): window creation was cancelled".to_string())?;
+
+        let window = WindowHandle::from(window);
+        if let Some(id) = self.id.as_ref() {
+            app.register_window(id, window.clone())?;
+        }
+
+        Ok(window)
+    }
+}
+
+/// Typed wrapper around [`nw_sys::Window`].
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+pub struct WindowHandle {
+    window: nw_sys::Window,
+}
+
+impl From<nw_sys::Window> for WindowHandle {
+    fn from(window: nw_sys::Window) -> Self {
+        Self { window }
+    }
+}
+
+impl PartialEq for WindowHandle {
+    fn eq(&self, other: &Self) -> bool {
+        let this: &JsValue = self.window.as_ref();
+        let other: &JsValue = other.window.as_ref();
+        this == other
+    }
+}
+
+impl WindowHandle {
+    /// Get a handle for the current window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#windowgetwindow_object)
+    pub fn current() -> Self {
+        Self::from(nw_sys::window::get())
+    }
+
+    /// The underlying [`nw_sys::Window`]
+    pub fn window(&self) -> &nw_sys::Window {
+        &self.window
+    }
+
+    pub(crate) fn ext(&self) -> &WindowExt {
+        self.window.unchecked_ref()
+    }
+
+    /// The DOM [`Window`](web_sys::Window) object of this window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winwindow)
+    pub fn dom_window(&self) -> web_sys::Window {
+        self.window.window()
+    }
+
+    /// Get the window title.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wintitle)
+    pub fn title(&self) -> String {
+        self.window.title()
+    }
+
+    /// Set the window title.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wintitle)
+    pub fn set_title(&self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    /// Get the left and top offset of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winx)
+    pub fn position(&self) -> (i32, i32) {
+        (self.window.x(), self.window.y())
+    }
+
+    /// Move the window to the given left and top offset.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winx)
+    pub fn move_to(&self, x: i32, y: i32) {
+        self.window.set_x(x);
+        self.window.set_y(y);
+    }
+
+    /// Get the width and height of the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winwidth)
+    pub fn size(&self) -> (u32, u32) {
+        (self.window.width(), self.window.height())
+    }
+
+    /// Get the position and size of the window.
+    pub fn frame_rect(&self) -> Rect {
+        let (x, y) = self.position();
+        let (width, height) = self.size();
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Resize the window to the given width and height.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winresizetowidth-height)
+    pub fn resize_to(&self, width: u32, height: u32) {
+        self.window.resize_to(width, height);
+    }
+
+    /// Set window’s minimum size.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetminimumsizewidth-height)
+    pub fn set_min_size(&self, width: u32, height: u32) {
+        self.window.set_minimum_size(width, height);
+    }
+
+    /// Set window’s maximum size.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetmaximumsizewidth-height)
+    pub fn set_max_size(&self, width: u32, height: u32) {
+        self.window.set_maximum_size(width, height);
+    }
+
+    /// Set whether the window is resizable.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetresizableresizable)
+    pub fn set_resizable(&self, resizable: bool) {
+        self.window.set_resizable(resizable);
+    }
+
+    /// Set whether the window should always stay on top of other windows.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetalwaysontoptop)
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.window.set_always_on_top(always_on_top);
+    }
+
+    /// Whether the window is always on top of other windows.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winisalwaysontop)
+    pub fn is_always_on_top(&self) -> bool {
+        self.window.is_always_on_top()
+    }
+
+    /// Focus on the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winfocus)
+    pub fn focus(&self) {
+        self.window.focus();
+    }
+
+    /// Move focus away from the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winblur)
+    pub fn blur(&self) {
+        self.window.blur();
+    }
+
+    /// Show the window if it’s not shown.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winshowis_show)
+    pub fn show(&self) {
+        self.window.show();
+    }
+
+    /// Hide the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winhide)
+    pub fn hide(&self) {
+        self.window.hide();
+    }
+
+    /// Close the window. The closing can be prevented by listening to the `close` event.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wincloseforce)
+    pub fn close(&self) {
+        self.window.close();
+    }
+
+    /// Close the window ignoring any `close` event listeners.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wincloseforce)
+    pub fn close_with_force(&self) {
+        self.window.close_impl(true);
+    }
+
+    /// Reload the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winreload)
+    pub fn reload(&self) {
+        self.window.reload();
+    }
+
+    /// Maximize the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winmaximize)
+    pub fn maximize(&self) {
+        self.window.maximize();
+    }
+
+    /// Unmaximize the window, i.e. the reverse of [`maximize()`](Self::maximize).
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winunmaximize)
+    pub fn unmaximize(&self) {
+        self.window.unmaximize();
+    }
+
+    /// Minimize the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winminimize)
+    pub fn minimize(&self) {
+        self.window.minimize();
+    }
+
+    /// Restore the window after it has been minimized.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winrestore)
+    pub fn restore(&self) {
+        self.window.restore();
+    }
+
+    /// Whether the window is in fullscreen mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winisfullscreen)
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.is_fullscreen()
+    }
+
+    /// Make the window fullscreen.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winenterfullscreen)
+    pub fn enter_fullscreen(&self) {
+        self.window.enter_fullscreen();
+    }
+
+    /// Leave the fullscreen mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winleavefullscreen)
+    pub fn leave_fullscreen(&self) {
+        self.window.leave_fullscreen();
+    }
+
+    /// Toggle the fullscreen mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wintogglefullscreen)
+    pub fn toggle_fullscreen(&self) {
+        self.window.toggle_fullscreen();
+    }
+
+    /// Whether the window is in kiosk mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winiskioskmode)
+    pub fn is_kiosk_mode(&self) -> bool {
+        self.window.is_kiosk_mode()
+    }
+
+    /// Enter the kiosk mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winenterkioskmode)
+    pub fn enter_kiosk_mode(&self) {
+        self.window.enter_kiosk_mode();
+    }
+
+    /// Leave the kiosk mode.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winleavekioskmode)
+    pub fn leave_kiosk_mode(&self) {
+        self.window.leave_kiosk_mode();
+    }
+
+    /// Set the progress shown on the taskbar or dock icon, within `[0, 1]`.
+    /// `None` removes the progress bar.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetprogressbarprogress)
+    pub fn set_progress_bar(&self, progress: Option<f64>) {
+        let progress = match progress {
+            Some(progress) => progress.clamp(0.0, 1.0),
+            None => -1.0,
+        };
+        self.ext().set_progress_bar(progress);
+    }
+
+    /// Set the badge label on the taskbar or dock icon.
+    /// An empty label removes the badge.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winsetbadgelabellabel)
+    pub fn set_badge_label(&self, label: &str) {
+        self.ext().set_badge_label(label);
+    }
+
+    /// Request the user's attention by flashing the taskbar entry or
+    /// bouncing the dock icon. `false` cancels the request.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winrequestattentionattention)
+    pub fn request_attention(&self, attention: bool) {
+        self.ext().request_attention(&JsValue::from(attention));
+    }
+
+    /// Open the devtools to inspect the window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winshowdevtoolsiframe-callback)
+    pub fn show_dev_tools(&self) {
+        self.window.show_dev_tools();
+    }
+
+    /// Close the devtools window.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winclosedevtools)
+    pub fn close_dev_tools(&self) {
+        self.window.close_dev_tools();
+    }
+
+    /// Capture the visible area of the window and return the encoded image.
+    ///
+    /// `quality` (0-100) applies to [`CaptureFormat::Jpeg`] only. `region` limits
+    /// the capture to a rectangle of the page, in CSS pixels relative to the
+    /// top-left corner of the viewport. When `quality` or `region` is given
+    /// `win.captureScreenshot()` is used, as `win.capturePage()` supports neither.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#wincapturepagecallback-config)
+    pub async fn capture_page(
+        &self,
+        format: CaptureFormat,
+        quality: Option<u8>,
+        region: Option<Rect>,
+    ) -> Result<Vec<u8>> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let (sender, receiver) = oneshot::channel::<std::result::Result<Vec<u8>, String>>();
+        let mut sender = Some(sender);
+        let mut send = move |result| {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(result);
+            }
+        };
+
+        if quality.is_none() && region.is_none() {
+            let mut callback = Callback::default();
+            let app_clone = app.clone();
+            let callback_id = callback.get_id();
+            callback.set_closure(move |buffer: JsValue| {
+                let _ = app_clone.callbacks.remove(&callback_id);
+                send(Ok(js_sys::Uint8Array::new(&buffer).to_vec()));
+            });
+
+            let config = js_sys::Object::new();
+            js_sys::Reflect::set(&config, &"format".into(), &format.as_str().into())?;
+            js_sys::Reflect::set(&config, &"datatype".into(), &"buffer".into())?;
+            self.ext()
+                .capture_page_with_config(callback.as_ref(), &config);
+            app.callbacks.retain(callback)?;
+        } else {
+            let mut callback = Callback::default();
+            let app_clone = app.clone();
+            let callback_id = callback.get_id();
+            callback.set_closure(move |err: JsValue, data: JsValue| {
+                let _ = app_clone.callbacks.remove(&callback_id);
+                let result = match data.as_string() {
+                    Some(data) if err.is_falsy() => Ok(buffer_from(&data, "base64").to_vec()),
+                    _ => Err(format!("capture_page(): {:?}", err)),
+                };
+                send(result);
+            });
+
+            let options = js_sys::Object::new();
+            js_sys::Reflect::set(&options, &"format".into(), &format.as_str().into())?;
+            if let Some(quality) = quality {
+                js_sys::Reflect::set(&options, &"quality".into(), &quality.min(100).into())?;
+            }
+            if let Some(region) = region {
+                let clip = js_sys::Object::new();
+                js_sys::Reflect::set(&clip, &"x".into(), &region.x.into())?;
+                js_sys::Reflect::set(&clip, &"y".into(), &region.y.into())?;
+                js_sys::Reflect::set(&clip, &"width".into(), &region.width.into())?;
+                js_sys::Reflect::set(&clip, &"height".into(), &region.height.into())?;
+                js_sys::Reflect::set(&clip, &"scale".into(), &1.into())?;
+                js_sys::Reflect::set(&options, &"clip".into(), &clip)?;
+            }
+            self.ext().capture_screenshot(&options, callback.as_ref());
+            app.callbacks.retain(callback)?;
+        }
+
+        let image = receiver
+            .await
+            .map_err(|_| "capture_page(): capture was cancelled".to_string())??;
+        Ok(image)
+    }
+
+    /// Capture the window with [`capture_page()`](Self::capture_page)
+    /// and write the image to the file at `path`.
+    pub async fn capture_page_to_file(
+        &self,
+        path: &str,
+        format: CaptureFormat,
+        quality: Option<u8>,
+        region: Option<Rect>,
+    ) -> Result<()> {
+        let image = self.capture_page(format, quality, region).await?;
+        fs::write_bytes(path, &image)
+    }
+
+    /// Subscribe to a window event. The callback is retained in
+    /// [`Application::callbacks`](crate::application::Application::callbacks)
+    /// and released when the window is closed or when [`off()`](Self::off) is called.
+    ///
+    /// Note that listening to [`WindowEvent::Close`] prevents the window from
+    /// closing; the callback is then responsible for calling
+    /// [`close_with_force()`](Self::close_with_force).
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#event-close)
+    pub fn on<F>(&self, event: WindowEvent, mut callback: F) -> Result<CallbackId>
+    where
+        F: FnMut(WindowEventData) -> std::result::Result<(), JsValue> + 'static,
+    {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let window = self.window.clone();
+        let listener = Callback::new(move |value: JsValue| -> std::result::Result<(), JsValue> {
+            callback(event.data(&window, value))
+        });
+        let listener_id = listener.get_id();
+        self.window.on(event.as_str(), listener.as_ref());
+        app.callbacks.retain(listener)?;
+
+        let mut cleanup = Callback::default();
+        let cleanup_id = cleanup.get_id();
+        let listener_id_ = listener_id.clone();
+        cleanup.set_closure(move |_: JsValue| {
+            if let Some(app) = app() {
+                let _ = app.callbacks.remove(&listener_id_);
+                let _ = app.callbacks.remove(&cleanup_id);
+                if let Ok(mut cleanups) = app.listener_cleanups.lock() {
+                    cleanups.remove(&listener_id_);
+                }
+            }
+        });
+        self.window
+            .on(WindowEvent::Closed.as_str(), cleanup.as_ref());
+        app.listener_cleanups
+            .lock()?
+            .insert(listener_id.clone(), cleanup.get_id());
+        app.callbacks.retain(cleanup)?;
+
+        Ok(listener_id)
+    }
+
+    /// Intercept requests to close this window. The async `handler` can
+    /// show a confirmation dialog or save data and decides whether the
+    /// window is allowed to close. Handlers are also consulted by
+    /// [`Application::request_close_all()`](crate::application::Application::request_close_all)
+    /// so that every window gets a chance to veto quitting the application.
+    ///
+    /// Closing via [`close_with_force()`](Self::close_with_force) bypasses the handler.
+    /// Registering a new handler replaces the previous one.
+    ///
+    /// ```rust
+    /// win.on_close_requested(|ctx| async move {
+    ///     if ctx.window.dom_window().confirm_with_message("Discard changes?").unwrap_or(false) {
+    ///         CloseDecision::Allow
+    ///     } else {
+    ///         CloseDecision::Veto
+    ///     }
+    /// })?;
+    /// ```
+    pub fn on_close_requested<F, Fut>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(CloseContext) -> Fut + 'static,
+        Fut: Future<Output = CloseDecision> + 'static,
+    {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        let handler: CloseHandler = Arc::new(move |ctx| Box::pin(handler(ctx)));
+        let is_registered = app.set_close_handler(self, handler)?;
+        if is_registered {
+            return Ok(());
+        }
+
+        let pending = Arc::new(Mutex::new(false));
+        let window = self.clone();
+        self.on(WindowEvent::Close, move |data| {
+            let quit = matches!(data, WindowEventData::Close { quit: true });
+            {
+                let mut pending = pending.lock().map_err(|err| format!("{:?}", err))?;
+                if *pending {
+                    return Ok(());
+                }
+                *pending = true;
+            }
+
+            let window = window.clone();
+            let pending = pending.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let decision = match app().and_then(|app| app.close_handler(&window).ok()?) {
+                    Some(handler) => {
+                        handler(CloseContext {
+                            window: window.clone(),
+                            quit,
+                        })
+                        .await
+                    }
+                    None => CloseDecision::Allow,
+                };
+                if let Ok(mut pending) = pending.lock() {
+                    *pending = false;
+                }
+                if decision == CloseDecision::Allow {
+                    window.close_with_force();
+                }
+            });
+
+            Ok(())
+        })?;
+
+        let window = self.clone();
+        self.on(WindowEvent::Closed, move |_| {
+            if let Some(app) = app() {
+                app.remove_close_handler(&window)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Unsubscribe a callback registered with [`on()`](Self::on).
+    pub fn off(&self, event: WindowEvent, id: &CallbackId) -> Result<()> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        if let Some(callback) = app.callbacks.remove(id)? {
+            self.ext()
+                .remove_listener(event.as_str(), callback.get_fn());
+        }
+
+        // release the cleanup callback registered by `on()`
+        let cleanup_id = app.listener_cleanups.lock()?.remove(id);
+        if let Some(cleanup_id) = cleanup_id {
+            if let Some(cleanup) = app.callbacks.remove(&cleanup_id)? {
+                self.ext()
+                    .remove_listener(WindowEvent::Closed.as_str(), cleanup.get_fn());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+//!
+//! Persistence of window geometry across application launches.
+//!
+//! [`WindowStateKeeper`] records position, size, maximized/fullscreen state
+//! and the display of every window that has an `id`, stores it in
+//! `localStorage` or in a JSON file under `nw.App.dataPath`, and applies
+//! it when a window with the same `id` is created again.
+//!
+//! # Synopsis
+//! ```rust
+//! let app = Application::new()?;
+//!
+//! // enable window state persistence for all windows with an `id`
+//! app.set_window_state_keeper(Some(WindowStateKeeper::file(None)?))?;
+//!
+//! // the window reopens where the user left it
+//! let options = nw_sys::window::Options::new()
+//!     .id("main")
+//!     .title("My App")
+//!     .width(800)
+//!     .height(600);
+//!
+//! app.create_window_with_callback("/root/index.html", &options, |_win| Ok(()))?;
+//! ```
+//!
+
+use crate::display::{displays, Display, Rect};
+use crate::fs;
+use crate::window::{WindowEvent, WindowEventData, WindowHandle};
+use nw_sys::{prelude::*, result::Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use workflow_dom::utils::window;
+
+/// Default `localStorage` key used to store window states
+pub const DEFAULT_STORAGE_KEY: &str = "workflow-nw-window-state";
+
+/// Saved geometry of a single window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    /// id of the display the window was on
+    pub display: Option<u64>,
+}
+
+impl WindowState {
+    fn rect(&self) -> Rect {
+        Rect {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Find the display containing the center of the window
+    pub fn find_display(&self, displays: &[Display]) -> Option<u64> {
+        let (cx, cy) = self.rect().center();
+        displays
+            .iter()
+            .find(|display| display.work_area.contains(cx, cy))
+            .map(|display| display.id)
+    }
+
+    /// Return a copy of this state adjusted so that the window is fully
+    /// located on one of the given displays. If the window center is not
+    /// on any display, it is moved to the display it was saved on or,
+    /// if that display is gone, to the first (primary) display.
+    pub fn clamp_to(&self, displays: &[Display]) -> WindowState {
+        let mut state = self.clone();
+        let display = state
+            .find_display(displays)
+            .or(state.display)
+            .and_then(|id| displays.iter().find(|display| display.id == id))
+            .or_else(|| displays.first());
+
+        if let Some(display) = display {
+            let area = display.work_area;
+            state.width = state.width.min(area.width);
+            state.height = state.height.min(area.height);
+            state.x = state.x.max(area.x).min(area.right() - state.width as i32);
+            state.y = state.y.max(area.y).min(area.bottom() - state.height as i32);
+            state.display = Some(display.id);
+        }
+
+        state
+    }
+}
+
+/// Where window states are persisted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowStateStorage {
+    /// `localStorage` under the given key
+    LocalStorage(String),
+    /// JSON file at the given path
+    File(String),
+}
+
+impl WindowStateStorage {
+    fn load(&self) -> Result<Option<String>> {
+        match self {
+            Self::LocalStorage(key) => Ok(local_storage()?.get_item(key)?),
+            Self::File(path) => {
+                if fs::exists(path) {
+                    Ok(Some(fs::read_to_string(path)?))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn store(&self, text: &str) -> Result<()> {
+        match self {
+            Self::LocalStorage(key) => local_storage()?.set_item(key, text)?,
+            Self::File(path) => fs::write_atomic(path, text)?,
+        }
+        Ok(())
+    }
+}
+
+fn local_storage() -> Result<web_sys::Storage> {
+    match window().local_storage()? {
+        Some(storage) => Ok(storage),
+        None => Err("localStorage is not available".to_string().into()),
+    }
+}
+
+/// Records, persists and restores window geometry keyed by window id.
+///
+/// For usage example please refer to [Examples](self)
+#[derive(Clone)]
+pub struct WindowStateKeeper {
+    storage: WindowStateStorage,
+    states: Arc<Mutex<HashMap<String, WindowState>>>,
+}
+
+impl WindowStateKeeper {
+    /// Create a keeper using the given storage, loading previously saved states.
+    pub fn new(storage: WindowStateStorage) -> Result<Self> {
+        let states = match storage.load()? {
+            Some(text) => serde_json::from_str(&text).unwrap_or_default(),
+            None => HashMap::new(),
+        };
+
+        Ok(Self {
+            storage,
+            states: Arc::new(Mutex::new(states)),
+        })
+    }
+
+    /// Create a keeper storing window states in `localStorage`
+    pub fn local_storage() -> Result<Self> {
+        Self::new(WindowStateStorage::LocalStorage(
+            DEFAULT_STORAGE_KEY.to_string(),
+        ))
+    }
+
+    /// Create a keeper storing window states in a JSON file.
+    /// Defaults to `window-state.json` under `nw.App.dataPath`.
+    pub fn file(path: Option<&str>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_string(),
+            None => fs::join(&nw_sys::app::data_path(), "window-state.json"),
+        };
+        Self::new(WindowStateStorage::File(path))
+    }
+
+    /// Get the saved state of the window `id`
+    pub fn get(&self, id: &str) -> Result<Option<WindowState>> {
+        Ok(self.states.lock()?.get(id).cloned())
+    }
+
+    /// Update the state of the window `id` and persist all states
+    pub fn set(&self, id: &str, state: WindowState) -> Result<()> {
+        self.states.lock()?.insert(id.to_string(), state);
+        self.save()
+    }
+
+    /// Persist all states to the storage
+    pub fn save(&self) -> Result<()> {
+        let text = serde_json::to_string(&*self.states.lock()?)
+            .map_err(|err| format!("unable to serialize window state: {}", err))?;
+        self.storage.store(&text)
+    }
+
+    /// Return a copy of the `options` with position and size set from
+    /// the saved state of window `id`, clamped to the available displays.
+    pub fn apply(
+        &self,
+        id: &str,
+        options: &nw_sys::window::Options,
+    ) -> Result<nw_sys::window::Options> {
+        let options: nw_sys::window::Options =
+            js_sys::Object::assign(&js_sys::Object::new(), options).unchecked_into();
+
+        let state = match self.get(id)? {
+            Some(state) => state.clamp_to(&displays()?),
+            None => return Ok(options),
+        };
+
+        Ok(options
+            .set("x", JsValue::from(state.x))
+            .set("y", JsValue::from(state.y))
+            .set("width", JsValue::from(state.width))
+            .set("height", JsValue::from(state.height)))
+    }
+
+    /// Restore maximized/fullscreen state of the `window` and start
+    /// recording its geometry under `id`. States are persisted when
+    /// the window is maximized, restored, changes fullscreen mode or closes.
+    pub fn track(&self, id: &str, window: &WindowHandle) -> Result<()> {
+        if let Some(state) = self.get(id)? {
+            if state.fullscreen {
+                window.enter_fullscreen();
+            } else if state.maximized {
+                window.maximize();
+            }
+        }
+
+        let (x, y) = window.position();
+        let (width, height) = window.size();
+        let initial = self.get(id)?.unwrap_or(WindowState {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            fullscreen: false,
+            display: None,
+        });
+        let current = Arc::new(Mutex::new(initial));
+        let minimized = Arc::new(Mutex::new(false));
+
+        let events = [
+            WindowEvent::Move,
+            WindowEvent::Resize,
+            WindowEvent::Minimize,
+            WindowEvent::Maximize,
+            WindowEvent::Restore,
+            WindowEvent::EnterFullscreen,
+            WindowEvent::LeaveFullscreen,
+            WindowEvent::Closed,
+        ];
+
+        for event in events {
+            let keeper = self.clone();
+            let id = id.to_string();
+            let current = current.clone();
+            let minimized = minimized.clone();
+            window.on(event, move |data| {
+                keeper
+                    .update(&id, &current, &minimized, data)
+                    .map_err(JsValue::from)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        current: &Mutex<WindowState>,
+        minimized: &Mutex<bool>,
+        data: WindowEventData,
+    ) -> Result<()> {
+        let mut state = current.lock()?;
+        let mut minimized = minimized.lock()?;
+        let persist = match data {
+            WindowEventData::Move { x, y } => {
+                if !*minimized && !state.maximized && !state.fullscreen {
+                    state.x = x;
+                    state.y = y;
+                }
+                false
+            }
+            WindowEventData::Resize { width, height } => {
+                if !*minimized && !state.maximized && !state.fullscreen {
+                    state.width = width;
+                    state.height = height;
+                }
+                false
+            }
+            WindowEventData::Minimize => {
+                *minimized = true;
+                false
+            }
+            WindowEventData::Maximize => {
+                state.maximized = true;
+                true
+            }
+            WindowEventData::Restore => {
+                if *minimized {
+                    *minimized = false;
+                } else {
+                    state.maximized = false;
+                }
+                true
+            }
+            WindowEventData::EnterFullscreen => {
+                state.fullscreen = true;
+                true
+            }
+            WindowEventData::LeaveFullscreen => {
+                state.fullscreen = false;
+                true
+            }
+            WindowEventData::Closed => true,
+            _ => false,
+        };
+
+        if persist {
+            if let Ok(displays) = displays() {
+                state.display = state.find_display(&displays).or(state.display);
+            }
+        }
+
+        self.states.lock()?.insert(id.to_string(), state.clone());
+        if persist {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(id: u64, x: i32, y: i32, width: u32, height: u32) -> Display {
+        let area = Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+        Display {
+            id,
+            scale_factor: 1.0,
+            bounds: area,
+            work_area: area,
+        }
+    }
+
+    fn state(x: i32, y: i32, width: u32, height: u32, display: Option<u64>) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            fullscreen: false,
+            display,
+        }
+    }
+
+    fn displays() -> Vec<Display> {
+        vec![
+            display(1, 0, 0, 1920, 1040),
+            display(2, 1920, 0, 1280, 1024),
+        ]
+    }
+
+    #[test]
+    fn visible_window_is_unchanged() {
+        let clamped = state(100, 100, 800, 600, None).clamp_to(&displays());
+        assert_eq!(clamped, state(100, 100, 800, 600, Some(1)));
+    }
+
+    #[test]
+    fn window_off_screen_moves_to_saved_display() {
+        let clamped = state(5000, 3000, 800, 600, Some(2)).clamp_to(&displays());
+        assert_eq!(clamped, state(2400, 424, 800, 600, Some(2)));
+    }
+
+    #[test]
+    fn window_off_screen_on_removed_display_moves_to_primary() {
+        let clamped = state(-4000, -3000, 800, 600, Some(7)).clamp_to(&displays());
+        assert_eq!(clamped, state(0, 0, 800, 600, Some(1)));
+    }
+
+    #[test]
+    fn window_partly_off_screen_is_moved_inside() {
+        let clamped = state(1500, -50, 800, 600, None).clamp_to(&displays());
+        assert_eq!(clamped, state(1120, 0, 800, 600, Some(1)));
+
+        let clamped = state(2600, 700, 800, 600, None).clamp_to(&displays());
+        assert_eq!(clamped, state(2400, 424, 800, 600, Some(2)));
+    }
+
+    #[test]
+    fn window_larger_than_display_is_shrunk() {
+        let clamped = state(100, 100, 2500, 1500, None).clamp_to(&displays());
+        assert_eq!(clamped, state(0, 0, 1920, 1040, Some(1)));
+    }
+
+    #[test]
+    fn no_displays_leaves_state_unchanged() {
+        let original = state(5000, 3000, 800, 600, Some(2));
+        assert_eq!(original.clamp_to(&[]), original);
+    }
+}
@@ -0,0 +1,248 @@
+//!
+//! Window zoom level management.
+//!
+//! [`ZoomBuilder`] restores the zoom level of a window saved under its id,
+//! persists it in `localStorage` whenever it changes and installs the
+//! standard `Ctrl` + `=` / `-` / `0` keyboard bindings (`⌘` on macOS).
+//!
+//! # Synopsis
+//! ```rust
+//! let win = WindowHandle::current();
+//!
+//! ZoomBuilder::new(&win)
+//!     .persist_as("main")
+//!     .keyboard_shortcuts(true)
+//!     .build()?;
+//!
+//! // or add the zoom commands to the View menu of the menubar
+//! let view = MenuItemBuilder::new()
+//!     .label("View")
+//!     .submenus(zoom_menu_items(&win)?)
+//!     .build()?;
+//!
+//! MenubarBuilder::new("My App")
+//!     .append(view)
+//!     .build(true)?;
+//!
+//! win.zoom_in();
+//! win.reset_zoom();
+//! ```
+//!
+
+use crate::application::app;
+use crate::menu::MenuItemBuilder;
+use crate::window::{WindowEvent, WindowEventData, WindowExt, WindowHandle};
+use nw_sys::{result::Result, MenuItem};
+use wasm_bindgen::prelude::*;
+use web_sys::KeyboardEvent;
+use workflow_dom::utils::window;
+use workflow_wasm::prelude::*;
+
+/// Zoom level change applied by [`WindowHandle::zoom_in()`]
+/// and [`WindowHandle::zoom_out()`], about 10% of the page size.
+pub const ZOOM_STEP: f64 = 0.5;
+/// Smallest zoom level, about 25%
+pub const MIN_ZOOM_LEVEL: f64 = -7.5;
+/// Largest zoom level, about 500%
+pub const MAX_ZOOM_LEVEL: f64 = 9.0;
+
+const STORAGE_KEY_PREFIX: &str = "workflow-nw-zoom:";
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(method, getter, js_name = zoomLevel)]
+    fn zoom_level(this: &WindowExt) -> f64;
+
+    #[wasm_bindgen(method, setter, js_name = zoomLevel)]
+    fn set_zoom_level(this: &WindowExt, level: f64);
+}
+
+impl WindowHandle {
+    /// Get the page zoom level. `0` is the normal size, each step
+    /// up or down scales the page by 20%.
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winzoomlevel)
+    pub fn zoom_level(&self) -> f64 {
+        self.ext().zoom_level()
+    }
+
+    /// Set the page zoom level, limited to
+    /// [`MIN_ZOOM_LEVEL`]..=[`MAX_ZOOM_LEVEL`].
+    ///
+    /// ⧉ [NWJS Documentation](https://docs.nwjs.io/en/latest/References/Window/#winzoomlevel)
+    pub fn set_zoom_level(&self, level: f64) {
+        self.ext()
+            .set_zoom_level(level.clamp(MIN_ZOOM_LEVEL, MAX_ZOOM_LEVEL));
+    }
+
+    /// Increase the zoom level by [`ZOOM_STEP`]
+    pub fn zoom_in(&self) {
+        self.set_zoom_level(self.zoom_level() + ZOOM_STEP);
+    }
+
+    /// Decrease the zoom level by [`ZOOM_STEP`]
+    pub fn zoom_out(&self) {
+        self.set_zoom_level(self.zoom_level() - ZOOM_STEP);
+    }
+
+    /// Restore the normal page size
+    pub fn reset_zoom(&self) {
+        self.set_zoom_level(0.0);
+    }
+}
+
+fn is_mac() -> bool {
+    window()
+        .navigator()
+        .platform()
+        .map(|platform| platform.starts_with("Mac"))
+        .unwrap_or(false)
+}
+
+fn storage_key(id: &str) -> String {
+    format!("{}{}", STORAGE_KEY_PREFIX, id)
+}
+
+fn local_storage() -> Result<web_sys::Storage> {
+    match window().local_storage()? {
+        Some(storage) => Ok(storage),
+        None => Err("localStorage is not available".to_string().into()),
+    }
+}
+
+/// Load the zoom level saved for the window `id`
+pub fn saved_zoom_level(id: &str) -> Result<Option<f64>> {
+    let level = local_storage()?
+        .get_item(&storage_key(id))?
+        .and_then(|level| level.parse::<f64>().ok());
+    Ok(level)
+}
+
+/// Provides a builder pattern for zoom persistence and keyboard bindings
+/// of a window.
+///
+/// For usage example please refer to [Examples](self)
+pub struct ZoomBuilder {
+    pub window: WindowHandle,
+    pub id: Option<String>,
+    pub keyboard_shortcuts: bool,
+}
+
+impl ZoomBuilder {
+    pub fn new(window: &WindowHandle) -> Self {
+        Self {
+            window: window.clone(),
+            id: None,
+            keyboard_shortcuts: true,
+        }
+    }
+
+    /// Restore and persist the zoom level under the window `id`,
+    /// usually the id given to [`WindowBuilder::id()`](crate::window::WindowBuilder::id).
+    pub fn persist_as(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Whether to handle `Ctrl` + `=` / `-` / `0` (`⌘` on macOS)
+    /// while the window has focus. Default is `true`.
+    ///
+    /// Disable it when the [`zoom_menu_items()`] are attached to the
+    /// menubar, as menu items handle the same keys.
+    pub fn keyboard_shortcuts(mut self, enable: bool) -> Self {
+        self.keyboard_shortcuts = enable;
+        self
+    }
+
+    pub fn build(self) -> Result<()> {
+        let app = match app() {
+            Some(app) => app,
+            None => return Err("app is not initialized".to_string().into()),
+        };
+
+        if let Some(id) = self.id {
+            if let Some(level) = saved_zoom_level(&id)? {
+                self.window.set_zoom_level(level);
+            }
+
+            self.window.on(WindowEvent::Zoom, move |data| {
+                if let WindowEventData::Zoom { level } = data {
+                    local_storage()?.set_item(&storage_key(&id), &level.to_string())?;
+                }
+                Ok(())
+            })?;
+        }
+
+        if self.keyboard_shortcuts {
+            let mac = is_mac();
+            let window = self.window.clone();
+            let callback = callback!(
+                move |event: KeyboardEvent| -> std::result::Result<(), JsValue> {
+                    let modifier = if mac {
+                        event.meta_key()
+                    } else {
+                        event.ctrl_key()
+                    };
+                    if !modifier || event.alt_key() {
+                        return Ok(());
+                    }
+                    match event.key().as_str() {
+                        "=" | "+" => window.zoom_in(),
+                        "-" | "_" => window.zoom_out(),
+                        "0" => window.reset_zoom(),
+                        _ => return Ok(()),
+                    }
+                    event.prevent_default();
+                    Ok(())
+                }
+            );
+            self.window
+                .dom_window()
+                .add_event_listener_with_callback("keydown", callback.as_ref())?;
+            app.callbacks.retain(callback)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Create "Zoom In", "Zoom Out" and "Actual Size" menu items
+/// controlling the zoom level of `window`, with the standard shortcuts.
+pub fn zoom_menu_items(window: &WindowHandle) -> Result<Vec<MenuItem>> {
+    let modifiers = if is_mac() { "cmd" } else { "ctrl" };
+
+    let window_ = window.clone();
+    let zoom_in = MenuItemBuilder::new()
+        .label("Zoom In")
+        .key("=")
+        .modifiers(modifiers)
+        .callback(move |_| -> std::result::Result<(), JsValue> {
+            window_.zoom_in();
+            Ok(())
+        })
+        .build()?;
+
+    let window_ = window.clone();
+    let zoom_out = MenuItemBuilder::new()
+        .label("Zoom Out")
+        .key("-")
+        .modifiers(modifiers)
+        .callback(move |_| -> std::result::Result<(), JsValue> {
+            window_.zoom_out();
+            Ok(())
+        })
+        .build()?;
+
+    let window_ = window.clone();
+    let reset = MenuItemBuilder::new()
+        .label("Actual Size")
+        .key("0")
+        .modifiers(modifiers)
+        .callback(move |_| -> std::result::Result<(), JsValue> {
+            window_.reset_zoom();
+            Ok(())
+        })
+        .build()?;
+
+    Ok(vec![zoom_in, zoom_out, reset])
+}